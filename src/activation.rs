@@ -0,0 +1,490 @@
+use std::env;
+use std::path::Path;
+
+use crate::constants::{BIN_DIR, CANDIDATES_DIR, CURRENT_DIR};
+use crate::helpers::{get_current_version, installed_versions};
+use crate::sdkmanrc::Entry;
+use crate::version::VersionReq;
+
+/// The environment a shell needs to use the versions of an `.sdkmanrc`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Activation {
+    pub path: String,
+    pub variables: Vec<(String, String)>,
+    pub activated: Vec<Entry>,
+    pub missing: Vec<Entry>,
+}
+
+/// The name of the variable pointing at the version of a candidate in use, e.g. `JAVA_HOME`.
+pub fn home_variable(candidate: &str) -> String {
+    format!("{}_HOME", candidate.to_uppercase().replace('-', "_"))
+}
+
+/// Replaces version ranges and wildcards with the highest installed version they match. Exact
+/// versions, and ranges that match nothing, are kept as written.
+pub fn resolve_entries(sdkman_dir: &Path, entries: &[Entry]) -> Vec<Entry> {
+    entries
+        .iter()
+        .map(|entry| {
+            let requirement = VersionReq::parse(&entry.version);
+            if requirement.is_exact() {
+                return entry.clone();
+            }
+            let installed = installed_versions(sdkman_dir.to_path_buf(), &entry.candidate);
+            match requirement.resolve(&installed) {
+                Some(version) => Entry {
+                    version: version.to_owned(),
+                    ..entry.clone()
+                },
+                None => entry.clone(),
+            }
+        })
+        .collect()
+}
+
+/// Computes the `PATH` and `*_HOME` variables that put every installed entry in use. Entries
+/// whose version is not installed are left out and reported as missing.
+pub fn activate(sdkman_dir: &Path, path: &str, entries: &[Entry]) -> Activation {
+    let mut activation = Activation {
+        path: path.to_string(),
+        variables: Vec::new(),
+        activated: Vec::new(),
+        missing: Vec::new(),
+    };
+    for entry in entries {
+        let version_path = sdkman_dir
+            .join(CANDIDATES_DIR)
+            .join(&entry.candidate)
+            .join(&entry.version);
+        if !version_path.is_dir() {
+            activation.missing.push(entry.clone());
+            continue;
+        }
+        let (path, home) = use_version(
+            sdkman_dir,
+            &activation.path,
+            &entry.candidate,
+            &entry.version,
+        );
+        activation.path = path;
+        activation.variables.push(home);
+        activation.activated.push(entry.clone());
+    }
+    activation
+}
+
+/// Computes the `PATH` and the `*_HOME` variable that put a single version of a candidate in
+/// use, replacing any other version of the candidate on the `PATH`.
+pub fn use_version(
+    sdkman_dir: &Path,
+    path: &str,
+    candidate: &str,
+    version: &str,
+) -> (String, (String, String)) {
+    let candidate_dir = sdkman_dir.join(CANDIDATES_DIR).join(candidate);
+    let version_path = candidate_dir.join(version);
+    let path = rewrite_path(path, &candidate_dir, Some(&version_path.join(BIN_DIR)));
+    let home = (
+        home_variable(candidate),
+        version_path.to_string_lossy().to_string(),
+    );
+    (path, home)
+}
+
+/// Computes the environment of a new shell: every candidate with a default version is put on
+/// the `PATH` through its `current` link, which its `*_HOME` variable points at as well.
+pub fn defaults(
+    sdkman_dir: &Path,
+    path: &str,
+    candidates: &[&str],
+) -> (String, Vec<(String, String)>) {
+    let mut path = path.to_string();
+    let mut variables = Vec::new();
+    for candidate in candidates {
+        let candidate_dir = sdkman_dir.join(CANDIDATES_DIR).join(candidate);
+        let current_dir = candidate_dir.join(CURRENT_DIR);
+        if !current_dir.is_dir() {
+            continue;
+        }
+        path = rewrite_path(&path, &candidate_dir, Some(&current_dir.join(BIN_DIR)));
+        variables.push((
+            home_variable(candidate),
+            current_dir.to_string_lossy().to_string(),
+        ));
+    }
+    (path, variables)
+}
+
+/// A candidate put back to its default version, or taken out of the environment when it has
+/// no default.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Reset {
+    pub candidate: String,
+    pub restored: bool,
+}
+
+/// The environment a shell needs to go back to the default versions.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Clearance {
+    pub path: String,
+    pub variables: Vec<(String, String)>,
+    pub unset: Vec<String>,
+    pub reset: Vec<Reset>,
+}
+
+/// Points every candidate that the shell uses at a version other than its default back at
+/// `candidates/<candidate>/current`. Candidates without a current link are removed from `PATH`
+/// and their `*_HOME` variable is unset. `variable` looks up the shell's environment.
+pub fn clear(
+    sdkman_dir: &Path,
+    path: &str,
+    variable: &dyn Fn(&str) -> Option<String>,
+    candidates: &[&str],
+) -> Clearance {
+    let mut clearance = Clearance {
+        path: path.to_string(),
+        variables: Vec::new(),
+        unset: Vec::new(),
+        reset: Vec::new(),
+    };
+    for candidate in candidates {
+        let candidate_dir = sdkman_dir.join(CANDIDATES_DIR).join(candidate);
+        let current_dir = candidate_dir.join(CURRENT_DIR);
+        let home_name = home_variable(candidate);
+        let home = variable(&home_name).filter(|home| belongs_to(home, &candidate_dir));
+        let on_path = env::split_paths(&clearance.path)
+            .any(|e| belongs_to(&e, &candidate_dir) && !belongs_to(&e, &current_dir));
+        let home_elsewhere = home.as_ref().is_some_and(|h| !belongs_to(h, &current_dir));
+        if !on_path && !home_elsewhere {
+            continue;
+        }
+
+        let restored = current_dir.is_dir();
+        if restored {
+            let bin_dir = current_dir.join(BIN_DIR);
+            clearance.path = rewrite_path(&clearance.path, &candidate_dir, Some(&bin_dir));
+            clearance
+                .variables
+                .push((home_name, current_dir.to_string_lossy().to_string()));
+        } else {
+            clearance.path = rewrite_path(&clearance.path, &candidate_dir, None);
+            clearance.unset.push(home_name);
+        }
+        clearance.reset.push(Reset {
+            candidate: candidate.to_string(),
+            restored,
+        });
+    }
+    clearance
+}
+
+/// Points the `PATH` entry of a candidate at `bin_dir`, or removes it when there is none. The
+/// candidate keeps its position when it is already on the `PATH`; otherwise `bin_dir` is
+/// prepended.
+pub fn rewrite_path(path: &str, candidate_dir: &Path, bin_dir: Option<&Path>) -> String {
+    let bin_dir = bin_dir.map(Path::to_path_buf);
+    let mut replaced = false;
+    let mut elements = Vec::new();
+    for element in env::split_paths(path).filter(|e| !e.as_os_str().is_empty()) {
+        if !belongs_to(&element, candidate_dir) {
+            elements.push(element);
+        } else if !replaced {
+            elements.extend(bin_dir.clone());
+            replaced = true;
+        }
+    }
+    if let (false, Some(bin_dir)) = (replaced, bin_dir) {
+        elements.insert(0, bin_dir);
+    }
+    env::join_paths(elements).map_or_else(
+        |_| path.to_string(),
+        |joined| joined.to_string_lossy().to_string(),
+    )
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Status {
+    Active,
+    Inactive,
+    Missing,
+}
+
+impl Status {
+    /// Exit codes of `sdk env check`, so that a pipeline can tell a missing version apart from
+    /// an installed one that the shell does not use. Both stay clear of 2, which usage errors
+    /// exit with.
+    pub fn exit_code(&self) -> i32 {
+        match self {
+            Status::Active => 0,
+            Status::Inactive => 3,
+            Status::Missing => 4,
+        }
+    }
+}
+
+/// How an `.sdkmanrc` entry compares with the installed versions and the shell's environment.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Check {
+    pub entry: Entry,
+    pub installed: bool,
+    pub active: Option<String>,
+    pub home: Option<String>,
+    pub status: Status,
+}
+
+/// Compares every entry with what is installed and with the versions the shell resolves through
+/// `PATH` and the `*_HOME` variables. `variable` looks up the shell's environment.
+pub fn check(
+    sdkman_dir: &Path,
+    path: &str,
+    variable: &dyn Fn(&str) -> Option<String>,
+    entries: &[Entry],
+) -> Vec<Check> {
+    entries
+        .iter()
+        .map(|entry| {
+            let candidate_dir = sdkman_dir.join(CANDIDATES_DIR).join(&entry.candidate);
+            let installed = candidate_dir.join(&entry.version).is_dir();
+            let active = active_version(sdkman_dir, path, &entry.candidate);
+            let home = variable(&home_variable(&entry.candidate))
+                .and_then(|home| version_of(sdkman_dir, &entry.candidate, &home));
+            let in_use = |version: &Option<String>| version.as_deref() == Some(&entry.version);
+            let status = if !installed {
+                Status::Missing
+            } else if in_use(&active) && (home.is_none() || in_use(&home)) {
+                Status::Active
+            } else {
+                Status::Inactive
+            };
+            Check {
+                entry: entry.clone(),
+                installed,
+                active,
+                home,
+                status,
+            }
+        })
+        .collect()
+}
+
+/// The version of a candidate that the shell runs, taken from the first `PATH` entry inside the
+/// candidate's folder.
+pub fn active_version(sdkman_dir: &Path, path: &str, candidate: &str) -> Option<String> {
+    let candidate_dir = sdkman_dir.join(CANDIDATES_DIR).join(candidate);
+    env::split_paths(path)
+        .find(|element| belongs_to(element, &candidate_dir))
+        .and_then(|element| version_of(sdkman_dir, candidate, &element))
+}
+
+// Resolves the version a path inside the candidate's folder belongs to, following the current
+// link to the version it points at.
+fn version_of(sdkman_dir: &Path, candidate: &str, element: impl AsRef<Path>) -> Option<String> {
+    let candidate_dir = sdkman_dir.join(CANDIDATES_DIR).join(candidate);
+    let version = element
+        .as_ref()
+        .strip_prefix(&candidate_dir)
+        .ok()?
+        .components()
+        .next()?
+        .as_os_str()
+        .to_string_lossy()
+        .to_string();
+    if version == CURRENT_DIR {
+        get_current_version(sdkman_dir.to_path_buf(), candidate)
+    } else {
+        Some(version)
+    }
+}
+
+fn belongs_to(element: impl AsRef<Path>, candidate_dir: &Path) -> bool {
+    element.as_ref().starts_with(candidate_dir)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::fs;
+    use std::path::{Path, PathBuf};
+
+    use tempfile::TempDir;
+
+    use crate::activation::{
+        activate, check, clear, home_variable, resolve_entries, rewrite_path, Reset, Status,
+    };
+    use crate::sdkmanrc::Entry;
+
+    fn entry(candidate: &str, version: &str) -> Entry {
+        Entry {
+            candidate: candidate.to_string(),
+            version: version.to_string(),
+            path: PathBuf::from(".sdkmanrc"),
+            line: 1,
+        }
+    }
+
+    #[test]
+    fn should_replace_candidate_path_in_place() {
+        let path = "/usr/bin:/sdkman/candidates/java/current/bin:/bin";
+        let rewritten = rewrite_path(
+            path,
+            Path::new("/sdkman/candidates/java"),
+            Some(Path::new("/sdkman/candidates/java/17.0.3-tem/bin")),
+        );
+        assert_eq!(
+            rewritten,
+            "/usr/bin:/sdkman/candidates/java/17.0.3-tem/bin:/bin"
+        );
+    }
+
+    #[test]
+    fn should_prepend_candidate_missing_from_path() {
+        let rewritten = rewrite_path(
+            "/usr/bin:/sdkman/candidates/javafx/current/bin",
+            Path::new("/sdkman/candidates/java"),
+            Some(Path::new("/sdkman/candidates/java/17.0.3-tem/bin")),
+        );
+        assert_eq!(
+            rewritten,
+            "/sdkman/candidates/java/17.0.3-tem/bin:/usr/bin:/sdkman/candidates/javafx/current/bin"
+        );
+    }
+
+    #[test]
+    fn should_activate_installed_entries_only() {
+        let dir = TempDir::new().unwrap();
+        fs::create_dir_all(dir.path().join("candidates/java/17.0.3-tem/bin")).unwrap();
+        let entries = [entry("java", "17.0.3-tem"), entry("maven", "3.9.6")];
+
+        let activation = activate(dir.path(), "/usr/bin", &entries);
+
+        let java_home = dir.path().join("candidates/java/17.0.3-tem");
+        assert_eq!(
+            activation.path,
+            format!("{}/bin:/usr/bin", java_home.display())
+        );
+        assert_eq!(
+            activation.variables,
+            vec![("JAVA_HOME".to_string(), java_home.display().to_string())]
+        );
+        assert_eq!(activation.activated, vec![entry("java", "17.0.3-tem")]);
+        assert_eq!(activation.missing, vec![entry("maven", "3.9.6")]);
+    }
+
+    #[test]
+    fn should_name_home_variable_after_candidate() {
+        assert_eq!(home_variable("java"), "JAVA_HOME");
+        assert_eq!(home_variable("spring-boot"), "SPRING_BOOT_HOME");
+    }
+
+    #[test]
+    fn should_clear_candidates_back_to_their_defaults() {
+        let dir = TempDir::new().unwrap();
+        let candidates = dir.path().join("candidates");
+        fs::create_dir_all(candidates.join("java/17.0.3-tem/bin")).unwrap();
+        fs::create_dir_all(candidates.join("java/current/bin")).unwrap();
+        fs::create_dir_all(candidates.join("maven/3.9.6/bin")).unwrap();
+        fs::create_dir_all(candidates.join("gradle/current/bin")).unwrap();
+        let path = format!(
+            "{}:/usr/bin:{}:{}",
+            candidates.join("java/17.0.3-tem/bin").display(),
+            candidates.join("maven/3.9.6/bin").display(),
+            candidates.join("gradle/current/bin").display()
+        );
+        let maven_home = candidates.join("maven/3.9.6").display().to_string();
+        let variable = |name: &str| (name == "MAVEN_HOME").then(|| maven_home.clone());
+
+        let clearance = clear(dir.path(), &path, &variable, &["java", "maven", "gradle"]);
+
+        let java_current = candidates.join("java/current");
+        assert_eq!(
+            clearance.path,
+            format!(
+                "{}/bin:/usr/bin:{}",
+                java_current.display(),
+                candidates.join("gradle/current/bin").display()
+            )
+        );
+        assert_eq!(
+            clearance.variables,
+            vec![("JAVA_HOME".to_string(), java_current.display().to_string())]
+        );
+        assert_eq!(clearance.unset, vec!["MAVEN_HOME".to_string()]);
+        assert_eq!(
+            clearance.reset,
+            vec![
+                Reset {
+                    candidate: "java".to_string(),
+                    restored: true
+                },
+                Reset {
+                    candidate: "maven".to_string(),
+                    restored: false
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn should_check_entries_against_installed_and_active_versions() {
+        let dir = TempDir::new().unwrap();
+        let candidates = dir.path().join("candidates");
+        fs::create_dir_all(candidates.join("java/17.0.3-tem/bin")).unwrap();
+        fs::create_dir_all(candidates.join("java/11.0.15-tem/bin")).unwrap();
+        fs::create_dir_all(candidates.join("maven/3.9.6/bin")).unwrap();
+        symlink::symlink_dir(
+            candidates.join("maven/3.9.6"),
+            candidates.join("maven/current"),
+        )
+        .unwrap();
+        let path = format!(
+            "{}:{}",
+            candidates.join("java/17.0.3-tem/bin").display(),
+            candidates.join("maven/current/bin").display()
+        );
+        let java_home = candidates.join("java/11.0.15-tem").display().to_string();
+        let variable = |name: &str| (name == "JAVA_HOME").then(|| java_home.clone());
+        let entries = [
+            entry("java", "17.0.3-tem"),
+            entry("maven", "3.9.6"),
+            entry("gradle", "8.5"),
+        ];
+
+        let checks = check(dir.path(), &path, &variable, &entries);
+
+        let summary = checks
+            .iter()
+            .map(|c| (c.active.as_deref(), c.home.as_deref(), c.status))
+            .collect::<Vec<_>>();
+        assert_eq!(
+            summary,
+            vec![
+                (Some("17.0.3-tem"), Some("11.0.15-tem"), Status::Inactive),
+                (Some("3.9.6"), None, Status::Active),
+                (None, None, Status::Missing),
+            ]
+        );
+    }
+
+    #[test]
+    fn should_resolve_ranges_against_installed_versions() {
+        let dir = TempDir::new().unwrap();
+        for version in ["21.0.1-tem", "21.0.2-tem", "21.0.3-zulu", "current"] {
+            fs::create_dir_all(dir.path().join("candidates/java").join(version)).unwrap();
+        }
+        let entries = [
+            entry("java", "21.*-tem"),
+            entry("maven", "3.9+"),
+            entry("gradle", "8.5"),
+        ];
+
+        let resolved = resolve_entries(dir.path(), &entries);
+
+        assert_eq!(
+            resolved,
+            vec![
+                entry("java", "21.0.2-tem"),
+                entry("maven", "3.9+"),
+                entry("gradle", "8.5")
+            ]
+        );
+    }
+}
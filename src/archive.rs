@@ -0,0 +1,349 @@
+use std::fmt;
+use std::fs::{self, File};
+use std::io;
+use std::path::{Path, PathBuf};
+
+use flate2::read::GzDecoder;
+use tempfile::Builder;
+use xz2::read::XzDecoder;
+use zip::ZipArchive;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ArchiveFormat {
+    Zip,
+    TarGz,
+    TarXz,
+}
+
+impl ArchiveFormat {
+    pub fn from_path(path: &Path) -> Option<ArchiveFormat> {
+        let file_name = path.file_name()?.to_str()?.to_lowercase();
+        if file_name.ends_with(".zip") {
+            Some(ArchiveFormat::Zip)
+        } else if file_name.ends_with(".tar.gz") || file_name.ends_with(".tgz") {
+            Some(ArchiveFormat::TarGz)
+        } else if file_name.ends_with(".tar.xz") || file_name.ends_with(".txz") {
+            Some(ArchiveFormat::TarXz)
+        } else {
+            None
+        }
+    }
+
+    pub fn extension(&self) -> &'static str {
+        match self {
+            ArchiveFormat::Zip => "zip",
+            ArchiveFormat::TarGz => "tar.gz",
+            ArchiveFormat::TarXz => "tar.xz",
+        }
+    }
+}
+
+#[derive(Debug)]
+pub enum ArchiveError {
+    UnsupportedFormat(PathBuf),
+    AlreadyInstalled(PathBuf),
+    Empty(PathBuf),
+    Corrupt(PathBuf, String),
+    Io(io::Error),
+}
+
+impl fmt::Display for ArchiveError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ArchiveError::UnsupportedFormat(path) => {
+                write!(f, "unsupported archive format: {}", path.display())
+            }
+            ArchiveError::AlreadyInstalled(path) => {
+                write!(f, "target already exists: {}", path.display())
+            }
+            ArchiveError::Empty(path) => write!(f, "archive is empty: {}", path.display()),
+            ArchiveError::Corrupt(path, reason) => {
+                write!(f, "cannot extract {}: {}", path.display(), reason)
+            }
+            ArchiveError::Io(e) => write!(f, "{}", e),
+        }
+    }
+}
+
+impl std::error::Error for ArchiveError {}
+
+impl From<io::Error> for ArchiveError {
+    fn from(e: io::Error) -> Self {
+        ArchiveError::Io(e)
+    }
+}
+
+/// Extracts a downloaded archive into `version_path`.
+///
+/// The archive is unpacked into a staging folder under `tmp_dir`, a single top-level folder is
+/// stripped, and the result is moved into place with one rename. Nothing is left behind in
+/// `tmp_dir` or at `version_path` when extraction fails.
+pub fn extract_archive(
+    archive: &Path,
+    tmp_dir: &Path,
+    version_path: &Path,
+) -> Result<(), ArchiveError> {
+    let format = ArchiveFormat::from_path(archive)
+        .ok_or_else(|| ArchiveError::UnsupportedFormat(archive.to_path_buf()))?;
+    if version_path.exists() || version_path.is_symlink() {
+        return Err(ArchiveError::AlreadyInstalled(version_path.to_path_buf()));
+    }
+
+    fs::create_dir_all(tmp_dir)?;
+    let staging = Builder::new().prefix(".extract-").tempdir_in(tmp_dir)?;
+    unpack(format, archive, staging.path())?;
+
+    let root =
+        single_root(staging.path())?.ok_or_else(|| ArchiveError::Empty(archive.to_path_buf()))?;
+    if let Some(parent) = version_path.parent() {
+        fs::create_dir_all(parent)?;
+    }
+    fs::rename(root, version_path)?;
+
+    Ok(())
+}
+
+fn unpack(format: ArchiveFormat, archive: &Path, destination: &Path) -> Result<(), ArchiveError> {
+    let file = File::open(archive)?;
+    let corrupt =
+        |e: &dyn fmt::Display| ArchiveError::Corrupt(archive.to_path_buf(), e.to_string());
+    match format {
+        ArchiveFormat::Zip => ZipArchive::new(file)
+            .and_then(|mut zip| zip.extract(destination))
+            .map_err(|e| corrupt(&e)),
+        ArchiveFormat::TarGz => tar::Archive::new(GzDecoder::new(file))
+            .unpack(destination)
+            .map_err(|e| corrupt(&e)),
+        ArchiveFormat::TarXz => tar::Archive::new(XzDecoder::new(file))
+            .unpack(destination)
+            .map_err(|e| corrupt(&e)),
+    }
+}
+
+// Archives usually wrap their content in one top-level folder, which is dropped. Flat archives
+// are installed as they are.
+fn single_root(staging: &Path) -> io::Result<Option<PathBuf>> {
+    let entries = fs::read_dir(staging)?
+        .map(|entry| entry.map(|e| e.path()))
+        .filter(
+            |path| !matches!(path, Ok(p) if p.file_name().is_some_and(|name| name == "__MACOSX")),
+        )
+        .collect::<io::Result<Vec<PathBuf>>>()?;
+
+    match entries.as_slice() {
+        [] => Ok(None),
+        [only] if only.is_dir() && !only.is_symlink() => Ok(Some(only.to_owned())),
+        _ => Ok(Some(staging.to_path_buf())),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::fs::{self, File};
+    use std::io::Write;
+    use std::path::Path;
+
+    use flate2::write::GzEncoder;
+    use flate2::Compression;
+    use tempfile::TempDir;
+    use xz2::write::XzEncoder;
+    use zip::write::SimpleFileOptions;
+    use zip::ZipWriter;
+
+    use crate::archive::{extract_archive, ArchiveError, ArchiveFormat};
+
+    const FILES: [(&str, &str); 2] = [
+        ("sdk-1.0/bin/sdk", "#!/bin/sh\necho sdk\n"),
+        ("sdk-1.0/lib/readme.txt", "readme"),
+    ];
+
+    fn tar_fixture<W: Write>(writer: W, files: &[(&str, &str)]) -> W {
+        let mut builder = tar::Builder::new(writer);
+        for (name, content) in files {
+            let mut header = tar::Header::new_gnu();
+            header.set_size(content.len() as u64);
+            header.set_mode(0o755);
+            header.set_cksum();
+            builder
+                .append_data(&mut header, name, content.as_bytes())
+                .unwrap();
+        }
+        builder.into_inner().unwrap()
+    }
+
+    fn tar_gz_fixture(dir: &Path, files: &[(&str, &str)]) -> std::path::PathBuf {
+        let path = dir.join("fixture.tar.gz");
+        let encoder = GzEncoder::new(File::create(&path).unwrap(), Compression::fast());
+        tar_fixture(encoder, files).finish().unwrap();
+        path
+    }
+
+    fn tar_xz_fixture(dir: &Path, files: &[(&str, &str)]) -> std::path::PathBuf {
+        let path = dir.join("fixture.tar.xz");
+        let encoder = XzEncoder::new(File::create(&path).unwrap(), 6);
+        tar_fixture(encoder, files).finish().unwrap();
+        path
+    }
+
+    fn zip_fixture(dir: &Path, files: &[(&str, &str)]) -> std::path::PathBuf {
+        let path = dir.join("fixture.zip");
+        let mut zip = ZipWriter::new(File::create(&path).unwrap());
+        let options = SimpleFileOptions::default().unix_permissions(0o755);
+        for (name, content) in files {
+            zip.start_file(*name, options).unwrap();
+            zip.write_all(content.as_bytes()).unwrap();
+        }
+        zip.finish().unwrap();
+        path
+    }
+
+    fn assert_installed(version_path: &Path) {
+        let content = fs::read_to_string(version_path.join("bin").join("sdk")).unwrap();
+        assert_eq!(content, "#!/bin/sh\necho sdk\n");
+        assert!(version_path.join("lib").join("readme.txt").is_file());
+    }
+
+    fn assert_tmp_is_clean(tmp_dir: &Path) {
+        let leftovers = fs::read_dir(tmp_dir)
+            .unwrap()
+            .filter_map(|e| e.ok())
+            .filter(|e| e.file_name().to_string_lossy().starts_with(".extract-"))
+            .count();
+        assert_eq!(leftovers, 0);
+    }
+
+    #[test]
+    fn should_detect_archive_format_from_file_name() {
+        assert_eq!(
+            ArchiveFormat::from_path(Path::new("java-17.zip")),
+            Some(ArchiveFormat::Zip)
+        );
+        assert_eq!(
+            ArchiveFormat::from_path(Path::new("java-17.tar.gz")),
+            Some(ArchiveFormat::TarGz)
+        );
+        assert_eq!(
+            ArchiveFormat::from_path(Path::new("java-17.TAR.XZ")),
+            Some(ArchiveFormat::TarXz)
+        );
+        assert_eq!(ArchiveFormat::from_path(Path::new("java-17.bin")), None);
+    }
+
+    #[test]
+    fn should_extract_tar_gz_and_strip_top_level_folder() {
+        let sdkman_dir = TempDir::new().unwrap();
+        let tmp_dir = sdkman_dir.path().join("tmp");
+        let version_path = sdkman_dir.path().join("candidates/sdk/1.0");
+        let archive = tar_gz_fixture(sdkman_dir.path(), &FILES);
+
+        extract_archive(&archive, &tmp_dir, &version_path).unwrap();
+
+        assert_installed(&version_path);
+        assert_tmp_is_clean(&tmp_dir);
+    }
+
+    #[test]
+    fn should_extract_tar_xz_and_strip_top_level_folder() {
+        let sdkman_dir = TempDir::new().unwrap();
+        let tmp_dir = sdkman_dir.path().join("tmp");
+        let version_path = sdkman_dir.path().join("candidates/sdk/1.0");
+        let archive = tar_xz_fixture(sdkman_dir.path(), &FILES);
+
+        extract_archive(&archive, &tmp_dir, &version_path).unwrap();
+
+        assert_installed(&version_path);
+        assert_tmp_is_clean(&tmp_dir);
+    }
+
+    #[test]
+    fn should_extract_zip_and_strip_top_level_folder() {
+        let sdkman_dir = TempDir::new().unwrap();
+        let tmp_dir = sdkman_dir.path().join("tmp");
+        let version_path = sdkman_dir.path().join("candidates/sdk/1.0");
+        let archive = zip_fixture(sdkman_dir.path(), &FILES);
+
+        extract_archive(&archive, &tmp_dir, &version_path).unwrap();
+
+        assert_installed(&version_path);
+        assert_tmp_is_clean(&tmp_dir);
+    }
+
+    #[test]
+    #[cfg(unix)]
+    fn should_preserve_executable_permissions() {
+        use std::os::unix::fs::PermissionsExt;
+
+        let sdkman_dir = TempDir::new().unwrap();
+        let tmp_dir = sdkman_dir.path().join("tmp");
+        let version_path = sdkman_dir.path().join("candidates/sdk/1.0");
+        let archive = zip_fixture(sdkman_dir.path(), &FILES);
+
+        extract_archive(&archive, &tmp_dir, &version_path).unwrap();
+
+        let mode = fs::metadata(version_path.join("bin/sdk"))
+            .unwrap()
+            .permissions()
+            .mode();
+        assert_eq!(mode & 0o111, 0o111);
+    }
+
+    #[test]
+    fn should_keep_flat_archive_layout() {
+        let sdkman_dir = TempDir::new().unwrap();
+        let tmp_dir = sdkman_dir.path().join("tmp");
+        let version_path = sdkman_dir.path().join("candidates/sdk/1.0");
+        let flat = FILES.map(|(name, content)| (name.trim_start_matches("sdk-1.0/"), content));
+        let archive = tar_gz_fixture(sdkman_dir.path(), &flat);
+
+        extract_archive(&archive, &tmp_dir, &version_path).unwrap();
+
+        assert_installed(&version_path);
+    }
+
+    #[test]
+    fn should_leave_nothing_behind_for_corrupt_archive() {
+        let sdkman_dir = TempDir::new().unwrap();
+        let tmp_dir = sdkman_dir.path().join("tmp");
+        let version_path = sdkman_dir.path().join("candidates/sdk/1.0");
+        let archive = sdkman_dir.path().join("broken.tar.gz");
+        let mut bytes = Vec::new();
+        let encoder = GzEncoder::new(&mut bytes, Compression::fast());
+        tar_fixture(encoder, &FILES).finish().unwrap();
+        bytes.truncate(bytes.len() / 2);
+        fs::write(&archive, bytes).unwrap();
+
+        let result = extract_archive(&archive, &tmp_dir, &version_path);
+
+        assert!(matches!(result, Err(ArchiveError::Corrupt(_, _))));
+        assert!(!version_path.exists());
+        assert_tmp_is_clean(&tmp_dir);
+    }
+
+    #[test]
+    fn should_refuse_to_overwrite_existing_version() {
+        let sdkman_dir = TempDir::new().unwrap();
+        let tmp_dir = sdkman_dir.path().join("tmp");
+        let version_path = sdkman_dir.path().join("candidates/sdk/1.0");
+        fs::create_dir_all(&version_path).unwrap();
+        let archive = zip_fixture(sdkman_dir.path(), &FILES);
+
+        let result = extract_archive(&archive, &tmp_dir, &version_path);
+
+        assert!(matches!(result, Err(ArchiveError::AlreadyInstalled(_))));
+    }
+
+    #[test]
+    fn should_reject_unsupported_archive() {
+        let sdkman_dir = TempDir::new().unwrap();
+        let archive = sdkman_dir.path().join("sdk.rar");
+        fs::write(&archive, "rar").unwrap();
+
+        let result = extract_archive(
+            &archive,
+            &sdkman_dir.path().join("tmp"),
+            &sdkman_dir.path().join("candidates/sdk/1.0"),
+        );
+
+        assert!(matches!(result, Err(ArchiveError::UnsupportedFormat(_))));
+    }
+}
@@ -0,0 +1,168 @@
+use std::fs;
+use std::io::{self, Write};
+use std::path::Path;
+use std::{env, process};
+
+use clap::{Parser, Subcommand};
+use colored::Colorize;
+
+use sdkman_cli_native::config::{
+    config_path, effective_values, file_value, set_value, unset_value, value_kind, Config,
+    SettingError, DEFAULT_CONFIG,
+};
+use sdkman_cli_native::helpers::infer_sdkman_dir;
+
+#[derive(Parser, Debug)]
+#[command(
+    bin_name = "sdk config",
+    about = "sdk subcommand to read and change the SDKMAN configuration"
+)]
+struct Args {
+    #[command(subcommand)]
+    command: Option<Command>,
+}
+
+#[derive(Subcommand, Debug)]
+enum Command {
+    /// Print the effective value of a key
+    Get { key: String },
+    /// Assign a value to a key, keeping comments and key order
+    Set { key: String, value: String },
+    /// Print every key with its effective value and source
+    List,
+    /// Remove a key so that its default applies
+    Unset { key: String },
+}
+
+fn main() {
+    let args = Args::parse();
+    let sdkman_dir = infer_sdkman_dir();
+    Config::load(&sdkman_dir).apply();
+    let Some(command) = args.command else {
+        edit_config(&sdkman_dir);
+        return;
+    };
+    let content = read_config(&sdkman_dir);
+
+    match command {
+        Command::Get { key } => {
+            if value_kind(&key).is_none() {
+                fail(SettingError::UnknownKey(key));
+            }
+            let value = file_value(&content, &key)
+                .or_else(|| Config::default().get(&key))
+                .unwrap_or_default();
+            println!("{}", value);
+        }
+        Command::Set { key, value } => {
+            let updated = set_value(&content, &key, value.trim()).unwrap_or_else(|e| fail(e));
+            write_config(&sdkman_dir, &updated);
+            println!("{} set to {}.", key.bold(), value.trim().bold());
+        }
+        Command::List => {
+            for (key, value, source) in effective_values(&content) {
+                println!("{}={} {}", key, value, format!("({})", source).italic());
+            }
+        }
+        Command::Unset { key } => {
+            let updated = unset_value(&content, &key).unwrap_or_else(|e| fail(e));
+            write_config(&sdkman_dir, &updated);
+            let default = Config::default().get(&key).unwrap_or_default();
+            println!("{} reset to its default {}.", key.bold(), default.bold());
+        }
+    }
+}
+
+// Opens the file in the user's editor until it validates or the user stops fixing it. A broken
+// file still exits non-zero so that it is noticed before the next shell reads it.
+fn edit_config(sdkman_dir: &Path) {
+    let path = config_path(sdkman_dir);
+    if !path.exists() {
+        write_config(sdkman_dir, DEFAULT_CONFIG);
+    }
+    let editor = infer_editor();
+    loop {
+        open_editor(&editor, &path);
+        let (_, warnings) = Config::parse(&read_config(sdkman_dir));
+        if warnings.is_empty() {
+            return;
+        }
+        for warning in &warnings {
+            eprintln!("{}: {}", path.display(), warning);
+        }
+        if !confirm("Do you want to reopen the editor to fix the configuration?") {
+            process::exit(1);
+        }
+    }
+}
+
+fn infer_editor() -> String {
+    ["VISUAL", "EDITOR"]
+        .iter()
+        .filter_map(|name| env::var(name).ok())
+        .find(|editor| !editor.trim().is_empty())
+        .unwrap_or_else(|| "vi".to_string())
+}
+
+fn open_editor(editor: &str, path: &Path) {
+    let mut words = editor.split_whitespace();
+    let program = words.next().unwrap_or("vi");
+    let status = process::Command::new(program)
+        .args(words)
+        .arg(path)
+        .status();
+    match status {
+        Ok(status) if status.success() => {}
+        Ok(status) => {
+            eprintln!("{} exited with {}.", editor.bold(), status);
+            process::exit(status.code().unwrap_or(1));
+        }
+        Err(e) => {
+            eprintln!("cannot launch editor {}: {}", editor.bold(), e);
+            process::exit(1);
+        }
+    }
+}
+
+// An empty answer accepts, as in the other sdk prompts; a closed stdin declines.
+fn confirm(question: &str) -> bool {
+    print!("{} (Y/n): ", question);
+    io::stdout().flush().ok();
+    let mut answer = String::new();
+    match io::stdin().read_line(&mut answer) {
+        Ok(0) | Err(_) => {
+            println!();
+            false
+        }
+        Ok(_) => matches!(answer.trim().to_lowercase().as_str(), "" | "y" | "yes"),
+    }
+}
+
+fn read_config(sdkman_dir: &Path) -> String {
+    let path = config_path(sdkman_dir);
+    match fs::read_to_string(&path) {
+        Ok(content) => content,
+        Err(e) if e.kind() == io::ErrorKind::NotFound => String::new(),
+        Err(e) => {
+            eprintln!("cannot read {}: {}", path.display(), e);
+            process::exit(1);
+        }
+    }
+}
+
+fn write_config(sdkman_dir: &Path, content: &str) {
+    let path = config_path(sdkman_dir);
+    let written = path
+        .parent()
+        .map_or(Ok(()), fs::create_dir_all)
+        .and_then(|_| fs::write(&path, content));
+    if let Err(e) = written {
+        eprintln!("cannot write {}: {}", path.display(), e);
+        process::exit(1);
+    }
+}
+
+fn fail(error: SettingError) -> ! {
+    eprintln!("{}", error);
+    process::exit(1);
+}
@@ -1,12 +1,12 @@
-use std::fs;
-use std::path::PathBuf;
 use std::process;
 
 use clap::Parser;
 use colored::Colorize;
 
-use sdkman_cli_native::constants::{CANDIDATES_DIR, CURRENT_DIR};
-use sdkman_cli_native::helpers::{infer_sdkman_dir, known_candidates, validate_candidate};
+use sdkman_cli_native::config::Config;
+use sdkman_cli_native::helpers::{
+    get_current_version, infer_sdkman_dir, known_candidates, validate_candidate,
+};
 
 #[derive(Parser, Debug)]
 #[command(
@@ -21,6 +21,7 @@ struct Args {
 fn main() {
     let args = Args::parse();
     let sdkman_dir = infer_sdkman_dir();
+    Config::load(&sdkman_dir).apply();
     let all_candidates = known_candidates(sdkman_dir.to_owned());
 
     match args.candidate {
@@ -69,36 +70,3 @@ fn main() {
         }
     }
 }
-
-fn get_current_version(base_dir: PathBuf, candidate: &str) -> Option<String> {
-    // First check if the candidate is installed
-    let candidate_dir = base_dir.join(CANDIDATES_DIR).join(candidate);
-    if !candidate_dir.exists() || !candidate_dir.is_dir() {
-        return None;
-    }
-
-    // Check for current symlink
-    let current_link = candidate_dir.join(CURRENT_DIR);
-    if !current_link.exists() {
-        return None;
-    }
-
-    // Get the symlink target (which should be the version)
-    if let Ok(target) = fs::read_link(&current_link) {
-        // Extract the version from the path
-        return target
-            .file_name()
-            .and_then(|name| name.to_str())
-            .map(|s| s.to_string());
-    }
-
-    // If this is not a symlink but a directory (fallback case)
-    if current_link.is_dir() {
-        return current_link
-            .file_name()
-            .and_then(|name| name.to_str())
-            .map(|s| s.to_string());
-    }
-
-    None
-}
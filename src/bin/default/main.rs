@@ -6,6 +6,7 @@ use std::fs;
 use std::fs::remove_dir_all;
 use symlink::{remove_symlink_dir, symlink_dir};
 
+use sdkman_cli_native::config::Config;
 use sdkman_cli_native::constants::{CANDIDATES_DIR, CURRENT_DIR, TMP_DIR};
 use sdkman_cli_native::helpers::{
     infer_sdkman_dir, known_candidates, validate_candidate, validate_version_path,
@@ -28,6 +29,7 @@ fn main() {
     let candidate = args.candidate;
     let version = args.version;
     let sdkman_dir = infer_sdkman_dir();
+    Config::load(&sdkman_dir).apply();
     let tmp_dir = sdkman_dir.join(TMP_DIR);
     let candidate = validate_candidate(known_candidates(sdkman_dir.to_owned()), &candidate);
     let version_path = validate_version_path(sdkman_dir.to_owned(), &candidate, &version);
@@ -38,10 +40,8 @@ fn main() {
 
     if current_link_path.exists() {
         remove_symlink_dir(&current_link_path).unwrap_or_else(|_| {
-            remove_dir_all(&current_link_path).expect(&format!(
-                "cannot remove current directory for {}.",
-                candidate
-            ))
+            remove_dir_all(&current_link_path)
+                .unwrap_or_else(|_| panic!("cannot remove current directory for {}.", candidate))
         })
     }
     println!(
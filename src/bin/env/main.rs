@@ -0,0 +1,437 @@
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::{env, process};
+
+use clap::{Parser, Subcommand};
+use colored::Colorize;
+
+use sdkman_cli_native::activation::{
+    activate, check, clear, home_variable, resolve_entries, Check, Status,
+};
+use sdkman_cli_native::config::Config;
+use sdkman_cli_native::constants::{
+    CANDIDATES_DIR, CURRENT_DIR, GIT_DIR, SDKMANRC_FILE, SDKMAN_ENV_ENV_VAR,
+};
+use sdkman_cli_native::download::{print_progress, DownloadSettings, Downloader};
+use sdkman_cli_native::helpers::{
+    format_table, get_current_version, infer_sdkman_dir, known_candidates, validate_candidate,
+};
+use sdkman_cli_native::installer::install_remote;
+use sdkman_cli_native::sdkmanrc::{merge, Entry, Sdkmanrc, HEADER};
+use sdkman_cli_native::shell::Shell;
+use sdkman_cli_native::version::VersionReq;
+
+#[derive(Parser, Debug)]
+#[command(
+    bin_name = "sdk env",
+    about = "sdk subcommand to control SDKs on a project level, setting up specific versions for a directory"
+)]
+struct Args {
+    #[command(subcommand)]
+    command: Option<Command>,
+
+    /// Shell to print activation code for, detected from $SHELL by default
+    #[arg(long, global = true, value_enum)]
+    shell: Option<Shell>,
+}
+
+#[derive(Subcommand, Debug)]
+enum Command {
+    /// Create an .sdkmanrc with the current default of every installed candidate
+    Init {
+        /// Only record these candidates
+        #[arg(long, value_delimiter = ',')]
+        only: Vec<String>,
+
+        /// Overwrite an existing .sdkmanrc
+        #[arg(long)]
+        force: bool,
+    },
+    /// Install every version of the .sdkmanrc that is missing, then use them
+    Install,
+    /// Reset every candidate to its default version
+    Clear,
+    /// Report whether every entry of the .sdkmanrc is installed and in use, without changes
+    Check,
+    /// Show the .sdkmanrc file and line that each entry comes from
+    Sources,
+    /// Switch versions after a directory change, printing only what differs from the shell
+    Hook {
+        /// The directory the shell was in before the change
+        previous: PathBuf,
+    },
+}
+
+// Everything but shell code goes to stderr, so that stdout can always be evaluated as is. Only
+// check and sources, which print no shell code, report on stdout and are not evaluated.
+fn main() {
+    let args = Args::parse();
+    let sdkman_dir = infer_sdkman_dir();
+    let config = Config::load(&sdkman_dir);
+    config.apply();
+    let shell = args.shell.unwrap_or_else(Shell::detect);
+
+    match args.command {
+        Some(Command::Init { only, force }) => init(&sdkman_dir, &only, force),
+        Some(Command::Install) => install(&sdkman_dir, &config, shell),
+        Some(Command::Clear) => clear_entries(&sdkman_dir, shell),
+        Some(Command::Check) => check_entries(&sdkman_dir),
+        Some(Command::Sources) => sources(&sdkman_dir),
+        Some(Command::Hook { previous }) => hook(&sdkman_dir, &previous, shell),
+        None => use_entries(&sdkman_dir, &resolve(&sdkman_dir), shell, true),
+    }
+}
+
+fn use_entries(sdkman_dir: &Path, entries: &[Entry], shell: Shell, suggest_install: bool) {
+    let path = env::var("PATH").unwrap_or_default();
+    let activation = activate(sdkman_dir, &path, entries);
+
+    for entry in &activation.missing {
+        eprintln!(
+            "{}",
+            format!(
+                "Stop! {} {} is not installed.",
+                entry.candidate, entry.version
+            )
+            .red()
+        );
+    }
+    if suggest_install && !activation.missing.is_empty() {
+        eprintln!("Run {} to install it.", "sdk env install".italic());
+    }
+    for entry in &activation.activated {
+        eprintln!(
+            "Using {} version {} in this shell.",
+            entry.candidate.bold(),
+            entry.version.bold()
+        );
+    }
+
+    println!("{}", shell.export("PATH", &activation.path));
+    for (name, value) in &activation.variables {
+        println!("{}", shell.export(name, value));
+    }
+}
+
+fn install(sdkman_dir: &Path, config: &Config, shell: Shell) {
+    let entries = resolve(sdkman_dir);
+    let downloader = Downloader::new(DownloadSettings::from_config(config));
+    let (mut installed, mut skipped, mut failed) = (0, 0, 0);
+
+    for entry in &entries {
+        let (candidate, version) = (&entry.candidate, &entry.version);
+        if !VersionReq::parse(version).is_exact() {
+            eprintln!(
+                "No installed version of {} matches {}, pin a version to install it.",
+                candidate.bold(),
+                version.bold()
+            );
+            failed += 1;
+            continue;
+        }
+        let version_path = sdkman_dir
+            .join(CANDIDATES_DIR)
+            .join(candidate)
+            .join(version);
+        if version_path.is_dir() {
+            eprintln!(
+                "{} {} is already installed, skipping.",
+                candidate.bold(),
+                version.bold()
+            );
+            skipped += 1;
+            continue;
+        }
+        eprintln!("downloading {} {}...", candidate.bold(), version.bold());
+        match install_remote(
+            sdkman_dir,
+            candidate,
+            version,
+            &downloader,
+            &mut print_progress,
+        ) {
+            Ok(_) => {
+                eprintln!("installed {} {}.", candidate.bold(), version.bold());
+                installed += 1;
+            }
+            Err(e) => {
+                eprintln!(
+                    "cannot install {} {}: {}",
+                    candidate.bold(),
+                    version.bold(),
+                    e
+                );
+                failed += 1;
+            }
+        }
+    }
+
+    eprintln!(
+        "{} installed, {} skipped, {} failed.",
+        installed, skipped, failed
+    );
+    use_entries(sdkman_dir, &entries, shell, false);
+    if failed > 0 {
+        process::exit(1);
+    }
+}
+
+// Works without an .sdkmanrc, as the shell has usually left the project directory already.
+fn clear_entries(sdkman_dir: &Path, shell: Shell) {
+    let path = env::var("PATH").unwrap_or_default();
+    let candidates = known_candidates(sdkman_dir.to_path_buf());
+    let clearance = clear(sdkman_dir, &path, &|name| env::var(name).ok(), &candidates);
+
+    if clearance.reset.is_empty() {
+        eprintln!("No project specific versions in use.");
+        return;
+    }
+    for reset in &clearance.reset {
+        if reset.restored {
+            eprintln!(
+                "Restored {} to its {} version.",
+                reset.candidate.bold(),
+                "default".italic()
+            );
+        } else {
+            eprintln!(
+                "Removed {} from this shell, it has no {} version.",
+                reset.candidate.bold(),
+                "default".italic()
+            );
+        }
+    }
+
+    println!("{}", shell.export("PATH", &clearance.path));
+    for (name, value) in &clearance.variables {
+        println!("{}", shell.export(name, value));
+    }
+    for name in &clearance.unset {
+        println!("{}", shell.unset(name));
+    }
+}
+
+// Runs on every directory change, so the common case of staying within the same project must not
+// read any .sdkmanrc. $SDKMAN_ENV holds the nearest .sdkmanrc that the shell is set up for.
+fn hook(sdkman_dir: &Path, previous: &Path, shell: Shell) {
+    let current_dir = env::current_dir().expect("cannot determine the current directory.");
+    if current_dir.starts_with(previous)
+        && current_dir
+            .ancestors()
+            .take_while(|dir| *dir != previous)
+            .all(|dir| !dir.join(SDKMANRC_FILE).exists() && !dir.join(GIT_DIR).exists())
+    {
+        return;
+    }
+    let active = env::var(SDKMAN_ENV_ENV_VAR)
+        .ok()
+        .filter(|active| !active.is_empty())
+        .map(PathBuf::from);
+    let nearest = Sdkmanrc::nearest(&current_dir);
+    if nearest == active {
+        return;
+    }
+
+    let mut path = env::var("PATH").unwrap_or_default();
+    let mut variables = Vec::new();
+    let mut unset = Vec::new();
+    if active.is_some() {
+        let candidates = known_candidates(sdkman_dir.to_path_buf());
+        let clearance = clear(sdkman_dir, &path, &|name| env::var(name).ok(), &candidates);
+        path = clearance.path;
+        variables = clearance.variables;
+        unset = clearance.unset;
+    }
+    if let Some(nearest) = &nearest {
+        let activation = activate(sdkman_dir, &path, &resolve(sdkman_dir));
+        for entry in &activation.missing {
+            eprintln!(
+                "{}",
+                format!(
+                    "Stop! {} {} is not installed.",
+                    entry.candidate, entry.version
+                )
+                .red()
+            );
+        }
+        path = activation.path;
+        for (name, value) in activation.variables {
+            unset.retain(|n| *n != name);
+            variables.retain(|(n, _)| *n != name);
+            variables.push((name, value));
+        }
+        variables.push((
+            SDKMAN_ENV_ENV_VAR.to_string(),
+            nearest.to_string_lossy().to_string(),
+        ));
+    } else {
+        unset.push(SDKMAN_ENV_ENV_VAR.to_string());
+    }
+
+    if env::var("PATH").unwrap_or_default() != path {
+        println!("{}", shell.export("PATH", &path));
+    }
+    for (name, value) in &variables {
+        if env::var(name).ok().as_ref() != Some(value) {
+            println!("{}", shell.export(name, value));
+        }
+    }
+    for name in &unset {
+        if env::var_os(name).is_some() {
+            println!("{}", shell.unset(name));
+        }
+    }
+}
+
+fn check_entries(sdkman_dir: &Path) {
+    let entries = resolve(sdkman_dir);
+    let path = env::var("PATH").unwrap_or_default();
+    let checks = check(sdkman_dir, &path, &|name| env::var(name).ok(), &entries);
+
+    let rows = checks.iter().map(check_row).collect::<Vec<_>>();
+    print!(
+        "{}",
+        format_table(
+            &["CANDIDATE", "EXPECTED", "INSTALLED", "ACTIVE", "STATUS"],
+            &rows,
+        )
+    );
+
+    let worst = [Status::Missing, Status::Inactive]
+        .into_iter()
+        .find(|status| checks.iter().any(|c| c.status == *status));
+    if let Some(status) = worst {
+        process::exit(status.exit_code());
+    }
+}
+
+fn check_row(check: &Check) -> Vec<String> {
+    let active = check.active.clone().unwrap_or_else(|| "-".to_string());
+    let active = match &check.home {
+        Some(home) if Some(home) != check.active.as_ref() => format!(
+            "{} ({} {})",
+            active,
+            home_variable(&check.entry.candidate),
+            home
+        ),
+        _ => active,
+    };
+    let status = match check.status {
+        Status::Active => "ok",
+        Status::Inactive => "inactive",
+        Status::Missing => "missing",
+    };
+    vec![
+        check.entry.candidate.to_owned(),
+        check.entry.version.to_owned(),
+        if check.installed { "yes" } else { "no" }.to_string(),
+        active,
+        status.to_string(),
+    ]
+}
+
+fn sources(sdkman_dir: &Path) {
+    let rows = read_entries(sdkman_dir)
+        .iter()
+        .map(|entry| {
+            vec![
+                entry.candidate.to_owned(),
+                entry.version.to_owned(),
+                format!("{}:{}", entry.path.display(), entry.line),
+            ]
+        })
+        .collect::<Vec<_>>();
+    print!(
+        "{}",
+        format_table(&["CANDIDATE", "VERSION", "SOURCE"], &rows)
+    );
+}
+
+fn init(sdkman_dir: &Path, only: &[String], force: bool) {
+    let path = sdkmanrc_path();
+    if path.exists() && !force {
+        eprintln!(
+            "{} already exists! Use {} to overwrite it.",
+            SDKMANRC_FILE.bold(),
+            "--force".italic()
+        );
+        process::exit(1);
+    }
+
+    let all_candidates = known_candidates(sdkman_dir.to_path_buf());
+    let candidates = if only.is_empty() {
+        all_candidates.iter().map(|c| c.to_string()).collect()
+    } else {
+        only.iter()
+            .map(|c| validate_candidate(all_candidates.to_owned(), c.trim()))
+            .collect::<Vec<_>>()
+    };
+
+    let mut content = HEADER.to_string();
+    for candidate in candidates {
+        let current_dir = sdkman_dir
+            .join(CANDIDATES_DIR)
+            .join(&candidate)
+            .join(CURRENT_DIR);
+        match get_current_version(sdkman_dir.to_path_buf(), &candidate) {
+            Some(version) => content.push_str(&format!("{}={}\n", candidate, version)),
+            None if current_dir.exists() => eprintln!(
+                "Cannot tell which version of {} the {} directory is, skipping.",
+                candidate.bold(),
+                CURRENT_DIR.italic()
+            ),
+            None if !only.is_empty() => eprintln!(
+                "No current version of {} configured, skipping.",
+                candidate.bold()
+            ),
+            None => {}
+        }
+    }
+
+    fs::write(&path, content).unwrap_or_else(|e| panic!("cannot write {}: {}", path.display(), e));
+    eprintln!("{} created.", SDKMANRC_FILE.bold());
+}
+
+fn sdkmanrc_path() -> PathBuf {
+    env::current_dir()
+        .expect("cannot determine the current directory.")
+        .join(SDKMANRC_FILE)
+}
+
+// Ranges and wildcards are resolved against the installed versions, stating what each one
+// resolved to.
+fn resolve(sdkman_dir: &Path) -> Vec<Entry> {
+    let entries = read_entries(sdkman_dir);
+    let resolved = resolve_entries(sdkman_dir, &entries);
+    for (requested, entry) in entries.iter().zip(&resolved) {
+        if requested.version != entry.version {
+            eprintln!(
+                "Resolved {} {} to {}.",
+                entry.candidate.bold(),
+                requested.version.italic(),
+                entry.version.bold()
+            );
+        }
+    }
+    resolved
+}
+
+// Every .sdkmanrc from the current directory up to the root of the hierarchy contributes, with
+// nearer files taking priority.
+fn read_entries(sdkman_dir: &Path) -> Vec<Entry> {
+    let current_dir = env::current_dir().expect("cannot determine the current directory.");
+    let known_candidates = known_candidates(sdkman_dir.to_path_buf());
+    let files = Sdkmanrc::read_hierarchy(&current_dir, &known_candidates).unwrap_or_else(|e| {
+        eprintln!("{}", e);
+        process::exit(1);
+    });
+    if files.is_empty() {
+        eprintln!(
+            "Could not find {} in the current directory or its parents.\n\nRun {} to create it.",
+            SDKMANRC_FILE.bold(),
+            "sdk env init".italic()
+        );
+        process::exit(1);
+    }
+    merge(&files)
+}
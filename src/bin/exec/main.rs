@@ -0,0 +1,300 @@
+use std::env;
+use std::io::{self, BufRead, BufReader};
+use std::path::Path;
+use std::process::{self, Command, ExitStatus, Output, Stdio};
+use std::thread;
+use std::time::{Duration, Instant};
+
+use clap::Parser;
+use colored::Colorize;
+
+use sdkman_cli_native::activation::use_version;
+use sdkman_cli_native::config::Config;
+use sdkman_cli_native::helpers::{
+    format_table, infer_sdkman_dir, installed_versions, known_candidates, validate_candidate,
+    validate_version_path,
+};
+use sdkman_cli_native::version::VersionReq;
+
+#[derive(Parser, Debug)]
+#[command(
+    bin_name = "sdk exec",
+    about = "sdk subcommand to run a command with specific candidate versions"
+)]
+struct Args {
+    /// A candidate followed by its version, or candidate=version pairs
+    #[arg(required_unless_present("matrix"))]
+    versions: Vec<String>,
+
+    /// Run the command once per version, e.g. java=11.0.20-tem,17.0.8-tem
+    #[arg(long, value_name = "CANDIDATE=VERSIONS")]
+    matrix: Option<String>,
+
+    /// Run the versions of the matrix at the same time
+    #[arg(long, requires("matrix"))]
+    parallel: bool,
+
+    /// The command to run, after --
+    #[arg(last = true, required(true))]
+    command: Vec<String>,
+}
+
+/// The outcome of one version of a matrix, without a result when the version is not installed.
+struct Run {
+    candidate: String,
+    version: String,
+    result: Option<(io::Result<Output>, Duration)>,
+}
+
+// Neither the calling shell nor the defaults change, only the environment of the child.
+fn main() {
+    let args = Args::parse();
+    let sdkman_dir = infer_sdkman_dir();
+    Config::load(&sdkman_dir).apply();
+    let all_candidates = known_candidates(sdkman_dir.to_owned());
+
+    let selections = parse_selections(&args.versions)
+        .into_iter()
+        .map(|(candidate, version)| {
+            let candidate = validate_candidate(all_candidates.to_owned(), &candidate);
+            validate_version_path(sdkman_dir.to_owned(), &candidate, &version);
+            (candidate, version)
+        })
+        .collect::<Vec<_>>();
+
+    match args.matrix {
+        Some(matrix) => {
+            let (candidate, versions) = parse_matrix(&matrix);
+            let candidate = validate_candidate(all_candidates, &candidate);
+            let runs = run_matrix(
+                &sdkman_dir,
+                &selections,
+                &candidate,
+                &versions,
+                &args.command,
+                args.parallel,
+            );
+            print!("{}", summary(&runs));
+            if !runs.iter().all(passed) {
+                process::exit(1);
+            }
+        }
+        None => {
+            let status = child(&sdkman_dir, &selections, &args.command)
+                .status()
+                .unwrap_or_else(|e| {
+                    eprintln!("cannot run {}: {}", args.command[0].bold(), e);
+                    process::exit(127);
+                });
+            process::exit(exit_code(status));
+        }
+    }
+}
+
+// Accepts `java 17.0.3-tem` as well as `java=17.0.3-tem maven=3.9.6`, or a mix of both.
+fn parse_selections(arguments: &[String]) -> Vec<(String, String)> {
+    let mut selections = Vec::new();
+    let mut arguments = arguments.iter();
+    while let Some(argument) = arguments.next() {
+        let selection = match argument.split_once('=') {
+            Some((candidate, version)) => (candidate.to_string(), version.to_string()),
+            None => match arguments.next() {
+                Some(version) => (argument.to_string(), version.to_string()),
+                None => {
+                    eprintln!("No version given for {}.", argument.bold());
+                    process::exit(1);
+                }
+            },
+        };
+        selections.push(selection);
+    }
+    selections
+}
+
+fn parse_matrix(matrix: &str) -> (String, Vec<String>) {
+    let versions = matrix.split_once('=').map(|(candidate, versions)| {
+        let versions = versions
+            .split(',')
+            .map(|v| v.trim().to_string())
+            .filter(|v| !v.is_empty())
+            .collect::<Vec<_>>();
+        (candidate.trim().to_string(), versions)
+    });
+    match versions {
+        Some((candidate, versions)) if !versions.is_empty() => (candidate, versions),
+        _ => {
+            eprintln!(
+                "The matrix {} must name a candidate and its versions, e.g. {}.",
+                matrix.bold(),
+                "java=17.0.8-tem,21.0.2-tem".italic()
+            );
+            process::exit(1);
+        }
+    }
+}
+
+// Each version may also be a range or wildcard, which runs the highest installed match.
+fn run_matrix(
+    sdkman_dir: &Path,
+    selections: &[(String, String)],
+    candidate: &str,
+    versions: &[String],
+    command: &[String],
+    parallel: bool,
+) -> Vec<Run> {
+    let installed = installed_versions(sdkman_dir.to_path_buf(), candidate);
+    let jobs = versions
+        .iter()
+        .map(|version| {
+            let resolved = VersionReq::parse(version).resolve(&installed).cloned();
+            (version.to_owned(), resolved)
+        })
+        .collect::<Vec<_>>();
+
+    let run = |(version, resolved): &(String, Option<String>)| {
+        let result = resolved.as_ref().map(|resolved| {
+            let mut selections = selections.to_vec();
+            selections.push((candidate.to_string(), resolved.to_owned()));
+            let started = Instant::now();
+            let mut child = child(sdkman_dir, &selections, command);
+            let output = if parallel {
+                child.output()
+            } else {
+                stream(child, &label(candidate, resolved))
+            };
+            (output, started.elapsed())
+        });
+        Run {
+            candidate: candidate.to_string(),
+            version: resolved.to_owned().unwrap_or_else(|| version.to_owned()),
+            result,
+        }
+    };
+
+    if !parallel {
+        return jobs
+            .iter()
+            .map(|job| {
+                let run = run(job);
+                print_output(&run);
+                run
+            })
+            .collect();
+    }
+    let runs = thread::scope(|scope| {
+        let handles = jobs
+            .iter()
+            .map(|job| scope.spawn(|| run(job)))
+            .collect::<Vec<_>>();
+        handles
+            .into_iter()
+            .map(|handle| handle.join().expect("a matrix run panicked"))
+            .collect::<Vec<_>>()
+    });
+    runs.iter().for_each(print_output);
+    runs
+}
+
+// Prints every line of a sequential run as soon as the child writes it. The output it returns
+// is empty, as nothing is left to print.
+fn stream(mut child: Command, label: &str) -> io::Result<Output> {
+    let mut child = child
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()?;
+    let stdout = child.stdout.take().expect("stdout of the child is piped");
+    let stderr = child.stderr.take().expect("stderr of the child is piped");
+    thread::scope(|scope| {
+        scope.spawn(|| {
+            for line in BufReader::new(stdout).split(b'\n').map_while(Result::ok) {
+                println!("{} {}", label, String::from_utf8_lossy(&line).trim_end());
+            }
+        });
+        for line in BufReader::new(stderr).split(b'\n').map_while(Result::ok) {
+            eprintln!("{} {}", label, String::from_utf8_lossy(&line).trim_end());
+        }
+    });
+    Ok(Output {
+        status: child.wait()?,
+        stdout: Vec::new(),
+        stderr: Vec::new(),
+    })
+}
+
+fn child(sdkman_dir: &Path, selections: &[(String, String)], command: &[String]) -> Command {
+    let mut path = env::var("PATH").unwrap_or_default();
+    let mut child = Command::new(&command[0]);
+    child.args(&command[1..]);
+    for (candidate, version) in selections {
+        let (rewritten, (home_name, home)) = use_version(sdkman_dir, &path, candidate, version);
+        path = rewritten;
+        child.env(home_name, home);
+    }
+    child.env("PATH", &path);
+    child
+}
+
+// Every line of a run is prefixed with its version, so that the output stays readable when
+// runs are interleaved in a CI log.
+fn label(candidate: &str, version: &str) -> String {
+    format!("[{} {}]", candidate, version).bold().to_string()
+}
+
+// Parallel runs are buffered and printed once they are all done, one run after the other.
+fn print_output(run: &Run) {
+    let label = label(&run.candidate, &run.version);
+    match &run.result {
+        Some((Ok(output), _)) => {
+            for line in String::from_utf8_lossy(&output.stdout).lines() {
+                println!("{} {}", label, line);
+            }
+            for line in String::from_utf8_lossy(&output.stderr).lines() {
+                eprintln!("{} {}", label, line);
+            }
+        }
+        Some((Err(e), _)) => eprintln!("{} cannot run the command: {}", label, e),
+        None => eprintln!("{} is not installed, skipping.", label),
+    }
+}
+
+fn passed(run: &Run) -> bool {
+    matches!(&run.result, Some((Ok(output), _)) if output.status.success())
+}
+
+fn summary(runs: &[Run]) -> String {
+    let rows = runs
+        .iter()
+        .map(|run| {
+            let (result, duration) = match &run.result {
+                Some((Ok(output), duration)) if output.status.success() => {
+                    ("pass".to_string(), Some(duration))
+                }
+                Some((Ok(output), duration)) => (
+                    format!("fail (exit {})", exit_code(output.status)),
+                    Some(duration),
+                ),
+                Some((Err(_), duration)) => ("fail (not run)".to_string(), Some(duration)),
+                None => ("not installed".to_string(), None),
+            };
+            vec![
+                run.candidate.to_owned(),
+                run.version.to_owned(),
+                result,
+                duration.map_or("-".to_string(), |d| format!("{:.1}s", d.as_secs_f64())),
+            ]
+        })
+        .collect::<Vec<_>>();
+    format_table(&["CANDIDATE", "VERSION", "RESULT", "TIME"], &rows)
+}
+
+// A child killed by a signal exits like it would from a shell, with 128 plus the signal.
+fn exit_code(status: ExitStatus) -> i32 {
+    #[cfg(unix)]
+    {
+        use std::os::unix::process::ExitStatusExt;
+        if let Some(signal) = status.signal() {
+            return 128 + signal;
+        }
+    }
+    status.code().unwrap_or(1)
+}
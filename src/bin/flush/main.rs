@@ -0,0 +1,122 @@
+use std::ffi::OsStr;
+use std::fs;
+use std::fs::remove_dir_all;
+use std::path::Path;
+use std::time::Duration;
+
+use clap::Parser;
+use colored::Colorize;
+
+use sdkman_cli_native::config::Config;
+use sdkman_cli_native::constants::{METADATA_DIR, QUARANTINE_DIR, TMP_DIR, VAR_DIR};
+use sdkman_cli_native::download::partial_downloads;
+use sdkman_cli_native::helpers::infer_sdkman_dir;
+
+const VERSION_FILES: [&str; 2] = ["version", "version_native"];
+// A partial download modified more recently may still be written by a running install, which
+// would no longer be able to resume it.
+const STALE_PARTIAL_AGE: Duration = Duration::from_secs(60 * 60);
+
+#[derive(Parser, Debug)]
+#[command(
+    bin_name = "sdk flush",
+    about = "sdk subcommand used for flushing local temporal state of SDKMAN"
+)]
+struct Args {
+    #[arg(required(false), value_parser(["tmp", "metadata", "version"]))]
+    target: Option<String>,
+}
+
+fn main() {
+    let args = Args::parse();
+    let sdkman_dir = infer_sdkman_dir();
+    Config::load(&sdkman_dir).apply();
+
+    match args.target.as_deref() {
+        Some("tmp") => flush_tmp(&sdkman_dir),
+        Some("metadata") => flush_metadata(&sdkman_dir),
+        Some("version") => flush_version(&sdkman_dir),
+        _ => {
+            flush_tmp(&sdkman_dir);
+            flush_metadata(&sdkman_dir);
+            flush_version(&sdkman_dir);
+        }
+    }
+}
+
+// Archives rejected by checksum verification are kept for inspection, they are only reported.
+fn flush_tmp(sdkman_dir: &Path) {
+    let tmp_dir = sdkman_dir.join(TMP_DIR);
+    let (stale, fresh): (Vec<_>, Vec<_>) = partial_downloads(&tmp_dir)
+        .into_iter()
+        .partition(|path| is_stale(path));
+    let mut keep = vec![OsStr::new(QUARANTINE_DIR)];
+    keep.extend(fresh.iter().filter_map(|path| path.file_name()));
+    let flushed = clear_folder(&tmp_dir, &keep);
+    println!(
+        "{} item(s) flushed from {}, including {} partial download(s).",
+        flushed,
+        "tmp".italic(),
+        stale.len()
+    );
+
+    if !fresh.is_empty() {
+        println!(
+            "{} partial download(s) modified in the last hour kept, as an install may still be writing them.",
+            fresh.len()
+        );
+    }
+    let quarantined = fs::read_dir(tmp_dir.join(QUARANTINE_DIR)).map_or(0, |e| e.count());
+    if quarantined > 0 {
+        println!(
+            "{} quarantined archive(s) kept in {}.",
+            quarantined,
+            "tmp/quarantine".italic()
+        );
+    }
+}
+
+fn flush_metadata(sdkman_dir: &Path) {
+    let metadata_dir = sdkman_dir.join(VAR_DIR).join(METADATA_DIR);
+    let flushed = clear_folder(&metadata_dir, &[]);
+    println!("{} item(s) flushed from {}.", flushed, "metadata".italic());
+}
+
+fn flush_version(sdkman_dir: &Path) {
+    let var_dir = sdkman_dir.join(VAR_DIR);
+    for file in VERSION_FILES {
+        let path = var_dir.join(file);
+        if path.is_file() {
+            fs::remove_file(&path).expect("cannot remove version file.");
+        }
+    }
+    println!("{} file(s) flushed.", "version".italic());
+}
+
+fn is_stale(path: &Path) -> bool {
+    fs::metadata(path)
+        .and_then(|metadata| metadata.modified())
+        .map_or(true, |modified| {
+            modified.elapsed().is_ok_and(|age| age >= STALE_PARTIAL_AGE)
+        })
+}
+
+fn clear_folder(folder: &Path, keep: &[&OsStr]) -> usize {
+    let entries = match fs::read_dir(folder) {
+        Ok(entries) => entries
+            .filter_map(|e| e.ok())
+            .filter(|e| !keep.contains(&e.file_name().as_os_str()))
+            .collect::<Vec<_>>(),
+        Err(_) => return 0,
+    };
+    for entry in &entries {
+        let path = entry.path();
+        if path.is_dir() && !path.is_symlink() {
+            remove_dir_all(&path)
+        } else {
+            fs::remove_file(&path)
+        }
+        .unwrap_or_else(|_| panic!("cannot remove {}.", path.display()));
+    }
+    entries.len()
+}
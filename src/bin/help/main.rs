@@ -4,7 +4,11 @@ use clap::Command;
 use colored::Colorize;
 use textwrap::{fill, indent};
 
+use sdkman_cli_native::config::{Config, DEFAULT_CONFIG};
+use sdkman_cli_native::helpers::infer_sdkman_dir;
+
 fn main() {
+    Config::load(&infer_sdkman_dir()).apply();
     let default_error = format!(
         "error: no subcommand specified (use {} for help)",
         "sdk help".italic()
@@ -15,16 +19,20 @@ fn main() {
         .subcommand(Command::new("current").alias("c"))
         .subcommand(Command::new("default").alias("d"))
         .subcommand(Command::new("env").alias("e"))
+        .subcommand(Command::new("exec"))
         .subcommand(Command::new("flush"))
         .subcommand(Command::new("home").alias("h"))
+        .subcommand(Command::new("init"))
         .subcommand(Command::new("install").alias("i"))
         .subcommand(Command::new("list").alias("ls"))
         .subcommand(Command::new("selfupdate"))
+        .subcommand(Command::new("shim"))
         .subcommand(Command::new("uninstall").alias("rm"))
         .subcommand(Command::new("update"))
         .subcommand(Command::new("upgrade"))
         .subcommand(Command::new("use").alias("u"))
         .subcommand(Command::new("version").alias("v"))
+        .subcommand(Command::new("which"))
         .get_matches();
 
     let help = match args.subcommand_name() {
@@ -32,16 +40,20 @@ fn main() {
         Some("current") => current_help(),
         Some("default") => default_help(),
         Some("env") => env_help(),
+        Some("exec") => exec_help(),
         Some("flush") => flush_help(),
         Some("home") => home_help(),
+        Some("init") => init_help(),
         Some("install") => install_help(),
         Some("list") => list_help(),
         Some("selfupdate") => selfupdate_help(),
+        Some("shim") => shim_help(),
         Some("uninstall") => uninstall_help(),
         Some("update") => update_help(),
         Some("upgrade") => upgrade_help(),
         Some("use") => use_help(),
         Some("version") => version_help(),
+        Some("which") => which_help(),
         _ => main_help(),
     };
 
@@ -117,10 +129,10 @@ fn render(help: Help) -> String {
                         &fill(&sub.description, TEXT_WIDTH - desc_depth),
                         &desc_indent,
                     )[command.len()..];
-                    format!("{}{}\n", command.to_string(), description)
+                    format!("{}{}\n", command, description)
                 })
                 .collect();
-            return format!("{}\n{}\n", "SUBCOMMANDS & QUALIFIERS".bold(), lines);
+            format!("{}\n{}\n", "SUBCOMMANDS & QUALIFIERS".bold(), lines)
         })
         .collect();
 
@@ -134,7 +146,7 @@ fn render(help: Help) -> String {
                 indent(&config.snippet, indentation)
             )
         })
-        .unwrap_or_else(|| String::new());
+        .unwrap_or_default();
 
     let mnemonic = help
         .mnemonic
@@ -146,7 +158,7 @@ fn render(help: Help) -> String {
             );
             format!("{}\n{}\n\n", "MNEMONIC".bold(), indent(&text, indentation))
         })
-        .unwrap_or_else(|| String::new());
+        .unwrap_or_default();
 
     let exit_code = help
         .exit_code
@@ -157,7 +169,7 @@ fn render(help: Help) -> String {
                 indent(&fill(&m, TEXT_WIDTH), indentation)
             )
         })
-        .unwrap_or_else(|| String::new());
+        .unwrap_or_default();
 
     let examples = format!(
         "{}\n{}\n\n",
@@ -183,18 +195,22 @@ fn main_help() -> Help {
             Subcommand { command: "help".to_string(), description: "[subcommand]".italic().to_string() },
             Subcommand { command: "install".to_string(), description: "<candidate> [version] [path]".italic().to_string() },
             Subcommand { command: "uninstall".to_string(), description: "<candidate> <version>".italic().to_string() },
-            Subcommand { command: "list".to_string(), description: "[candidate]".italic().to_string() },
+            Subcommand { command: "list".to_string(), description: "[candidate] [--installed]".italic().to_string() },
             Subcommand { command: "use".to_string(), description: "<candidate> <version>".italic().to_string() },
             Subcommand { command: "config".to_string(), description: "no qualifier".to_string() },
             Subcommand { command: "default".to_string(), description: "<candidate> [version]".italic().to_string() },
             Subcommand { command: "home".to_string(), description: "<candidate> <version>".italic().to_string() },
             Subcommand { command: "env".to_string(), description: "[init|install|clear]".italic().to_string() },
             Subcommand { command: "current".to_string(), description: "[candidate]".italic().to_string() },
+            Subcommand { command: "exec".to_string(), description: "<candidate> <version> -- <command>".italic().to_string() },
             Subcommand { command: "upgrade".to_string(), description: "[candidate]".italic().to_string() },
             Subcommand { command: "version".to_string(), description: "no qualifier".to_string() },
             Subcommand { command: "selfupdate".to_string(), description: "[force]".italic().to_string() },
             Subcommand { command: "update".to_string(), description: "no qualifier".to_string() },
             Subcommand { command: "flush".to_string(), description: "[tmp|metadata|version]".italic().to_string() },
+            Subcommand { command: "init".to_string(), description: "[shell]".italic().to_string() },
+            Subcommand { command: "shim".to_string(), description: "rehash".italic().to_string() },
+            Subcommand { command: "which".to_string(), description: "<binary> [--all]".italic().to_string() },
         ]),
         examples: "sdk install java 17.0.0-tem\nsdk help install".to_string(),
         ..Default::default()
@@ -203,30 +219,40 @@ fn main_help() -> Help {
 
 fn config_help() -> Help {
     let config_file = "${SDKMAN_DIR}/etc/config";
-    let default_config = "\
----
-sdkman_auto_answer=false
-sdkman_auto_complete=true
-sdkman_auto_env=false
-sdkman_auto_update=true
-sdkman_beta_channel=false
-sdkman_checksum_enable=true
-sdkman_colour_enable=true
-sdkman_curl_connect_timeout=7
-sdkman_curl_max_time=10
-sdkman_debug_mode=false
-sdkman_healthcheck_enable=true
-sdkman_insecure_ssl=false
-sdkman_selfupdate_feature=true
----";
+    let default_config = format!("---\n{}---", DEFAULT_CONFIG);
     Help {
         cmd: "sdk config".to_string(),
         tagline: "sdk subcommand to edit the SDKMAN configuration file".to_string(),
-        synopsis: "sdk config".to_string(),
-        description: format!("This subcommand opens a text editor on the configuration file located at {}. \
-        The subcommand will infer the text editor from the {} environment variable. If the system does \
-        not set the {} environment variable, then vi is assumed as the default editor.", config_file.underline(),
-                             "EDITOR".italic(), "EDITOR".italic()),
+        synopsis: "sdk config [get|set|list|unset]".to_string(),
+        description: format!("This subcommand opens a text editor on the configuration file located at {}, \
+        creating it with the default configuration if it is missing. The subcommand will infer the text editor from \
+        the {} or {} environment variables. If the system sets neither, then vi is assumed as the default editor. \
+        When the editor exits, the file is validated: unknown keys and invalid values are reported, and the editor \
+        may be reopened to fix them. The optional qualifiers read and change single keys without an editor, keeping \
+        comments and the order of keys intact.",
+                             config_file.underline(), "VISUAL".italic(), "EDITOR".italic()),
+        exit_code: Some("The subcommand will return a non-zero exit code if the editor fails or if the configuration \
+        file is left with unknown keys or invalid values.".to_string()),
+        subcommands: Some(
+            vec![
+                Subcommand {
+                    command: "get".to_string(),
+                    description: "print the effective value of a key".to_string(),
+                },
+                Subcommand {
+                    command: "set".to_string(),
+                    description: "assign a value to a key, validated against the type of the key".to_string(),
+                },
+                Subcommand {
+                    command: "list".to_string(),
+                    description: format!("print every key with its effective value and whether it comes from the {} \
+                    or the {}", "default".italic(), "file".italic()),
+                },
+                Subcommand {
+                    command: "unset".to_string(),
+                    description: "remove a key from the file so that its default applies".to_string(),
+                },
+            ]),
         configuration: Some(
             Configuration {
                 content: format!("The {} file contains the following default configuration. A new shell should be \
@@ -234,7 +260,7 @@ sdkman_selfupdate_feature=true
                 snippet: default_config.italic().to_string(),
             }
         ),
-        examples: "sdk config".to_string(),
+        examples: "sdk config\nsdk config get sdkman_auto_answer\nsdk config set sdkman_auto_answer true\nsdk config list\nsdk config unset sdkman_auto_answer".to_string(),
         ..Default::default()
     }
 }
@@ -282,13 +308,24 @@ java=11.0.13-tem
         cmd: "sdk env".to_string(),
         tagline: "sdk subcommand to control SDKs on a project level, setting up specific versions for a directory"
             .to_string(),
-        synopsis: "sdk env [init|install|clear]".to_string(),
+        synopsis: "sdk env [init [--only <candidates>] [--force]|install|clear|check|sources|hook <previous>] [--shell <shell>]".to_string(),
         description: format!("Allows the developer to manage the SDK versions used in a project directory. The \
         subcommand uses an {} file to install or switch specific SDK versions in a project directory.\n\nWhen \
         issuing the subcommand without a qualifier, it will switch to the versions specified in {} and emit \
-        warnings for versions not present on the system. In addition, the subcommand has three optional qualifiers.",
+        warnings for versions not present on the system. Besides exact versions, an entry may hold a wildcard such \
+        as {}, a lower bound such as {} or a range such as {}, which resolves to the highest matching installed \
+        version.\n\nThe {} files of parent directories are merged, with nearer files taking priority, up to the root of \
+        the git repository or a file containing {}. The activation code is printed for the shell named in {}, unless \
+        {} selects one of bash, zsh, fish, pwsh or nu. In addition, the subcommand has six optional qualifiers.",
                              ".sdkmanrc".underline(),
-                             ".sdkmanrc".underline()),
+                             ".sdkmanrc".underline(),
+                             "21.*-tem".italic(),
+                             "17+".italic(),
+                             ">=17 <18".italic(),
+                             ".sdkmanrc".underline(),
+                             "root=true".italic(),
+                             "$SHELL".underline(),
+                             "--shell".italic()),
         subcommands: Some(
             vec![
                 Subcommand {
@@ -297,12 +334,30 @@ java=11.0.13-tem
                 },
                 Subcommand {
                     command: "init".to_string(),
-                    description: format!("allows for the creation of a default {} file with a single entry for the {} \
-                    candidate, set to the current default value)", ".sdkmanrc".underline(), "java".italic()),
+                    description: format!("allows for the creation of a default {} file with an entry for every \
+                    installed candidate, set to its current default value. Restrict the entries with {} and \
+                    overwrite an existing file with {}", ".sdkmanrc".underline(), "--only java,maven".italic(),
+                                         "--force".italic()),
                 },
                 Subcommand {
                     command: "clear".to_string(),
-                    description: "reset all SDK versions to their system defaults".to_string(),
+                    description: "reset all SDK versions to their system defaults, removing SDKs without a default \
+                    version from the shell".to_string(),
+                },
+                Subcommand {
+                    command: "check".to_string(),
+                    description: format!("report whether every version in {} is installed and active in the current \
+                    shell, without changing anything", ".sdkmanrc".underline()),
+                },
+                Subcommand {
+                    command: "sources".to_string(),
+                    description: format!("show the {} file and line that each entry comes from", ".sdkmanrc".underline()),
+                },
+                Subcommand {
+                    command: "hook".to_string(),
+                    description: format!("switch versions after the shell changed directory from {}, printing only \
+                    what differs from the shell. Nothing is printed while the nearest {} stays the one recorded in {}",
+                                         "<previous>".italic(), ".sdkmanrc".underline(), "$SDKMAN_ENV".underline()),
                 },
             ]),
         configuration: Some(
@@ -321,7 +376,35 @@ java=11.0.13-tem
                 snippet: config_file_content.italic().to_string(),
             }
         ),
-        examples: "sdk env\nsdk env install\nsdk env init\nsdk env clear".to_string(),
+        exit_code: Some(format!("The {} qualifier reports the versions it installed, skipped or failed to install, and \
+        returns a non-zero exit code if any entry of {} could not be installed. The {} qualifier returns an exit code \
+        of 4 if a version is not installed and 3 if a version is installed but not active in the current shell.",
+                                "install".italic(), ".sdkmanrc".underline(), "check".italic())),
+        examples: "sdk env\nsdk env install\nsdk env init\nsdk env init --only java,maven --force\nsdk env clear\nsdk env check\nsdk env sources\nsdk env hook \"$OLDPWD\"\nsdk env --shell fish".to_string(),
+        ..Default::default()
+    }
+}
+
+fn exec_help() -> Help {
+    Help {
+        cmd: "sdk exec".to_string(),
+        tagline: "sdk subcommand to run a command with specific candidate versions".to_string(),
+        synopsis: "sdk exec <candidate> <version> [<candidate>=<version>...] -- <command>\n\
+        sdk exec --matrix <candidate>=<version>,<version>... [--parallel] [<candidate>=<version>...] -- <command>"
+            .to_string(),
+        description: format!("Run a command with the given versions first on its {} and their {} variables set. \
+        Several versions may be given as {} pairs. Neither the current shell nor the {} versions change, which \
+        makes the subcommand safe to use in scripts and CI.\n\nWith {}, the command runs once for every version \
+        of a candidate, one after the other or all at once with {}. A version may also be a range such as {}. The \
+        output of every run is prefixed with its version, and a table summarises which runs passed.",
+                             "PATH".underline(), "*_HOME".underline(), "candidate=version".italic(),
+                             "default".italic(), "--matrix".italic(), "--parallel".italic(), "17.*".italic()),
+        exit_code: Some("The subcommand returns the exit code of the command, 1 if a version is not installed and \
+        127 if the command cannot be run. A matrix returns 1 if any run failed or any of its versions is not \
+        installed.".to_string()),
+        examples: "sdk exec java 17.0.3-tem -- ./gradlew test\nsdk exec java=21.0.2-tem maven=3.9.6 -- mvn verify\n\
+        sdk exec --matrix java=11.0.20-tem,17.0.8-tem,21.* --parallel -- mvn -q verify"
+            .to_string(),
         ..Default::default()
     }
 }
@@ -339,11 +422,13 @@ fn flush_help() -> Help {
         subcommands: Some(vec![
             Subcommand {
                 command: "tmp".to_string(),
-                description: format!("cleans out pre/post hooks and residual archives from {}", "$SDKMAN_DIR/tmp".underline()),
+                description: format!("cleans out pre/post hooks, residual archives and partial downloads older \
+                than an hour from {}, keeping archives quarantined in {}",
+                                     "$SDKMAN_DIR/tmp".underline(), "$SDKMAN_DIR/tmp/quarantine".underline()),
             },
             Subcommand {
                 command: "metadata".to_string(),
-                description: format!("removes any header metadata"),
+                description: "removes any header metadata".to_string(),
             },
             Subcommand {
                 command: "version".to_string(),
@@ -375,6 +460,27 @@ fn home_help() -> Help {
     }
 }
 
+fn init_help() -> Help {
+    Help {
+        cmd: "sdk init".to_string(),
+        tagline: "sdk subcommand to print the code that sets up SDKMAN in a new shell".to_string(),
+        synopsis: "sdk init [bash|zsh|fish|pwsh|nu]".to_string(),
+        description: format!("Print the code that a shell evaluates on startup. It puts the {} version of every \
+        installed candidate in front of the {}, sets the matching {} variables and defines the {} function, which \
+        hands subcommands without a native binary to the bash implementation. With {} set, it also adds a hook \
+        that runs {} whenever the shell changes directory. The code is \
+        printed for the shell named in {} when no shell is given. As it only changes when a default version \
+        changes, the output may also be cached in a file that the shell sources. As the {} function does not exist yet \
+        at startup, the binary is run from {} directly. In shim mode, {} is put first on the {}.", "default".italic(),
+                             "PATH".underline(), "*_HOME".underline(), "sdk".italic(), "sdkman_auto_env=true".italic(),
+                             "sdk env hook".italic(), "$SHELL".underline(), "sdk".italic(),
+                             "$SDKMAN_DIR/libexec".underline(), "$SDKMAN_DIR/shims".underline(), "PATH".underline()),
+        examples: "eval \"$(\"$SDKMAN_DIR/libexec/init\" bash)\"\n$SDKMAN_DIR/libexec/init fish | source\n& $env:SDKMAN_DIR/libexec/init pwsh | Out-String | Invoke-Expression"
+            .to_string(),
+        ..Default::default()
+    }
+}
+
 fn install_help() -> Help {
     Help {
         cmd: "sdk install".to_string(),
@@ -386,7 +492,9 @@ fn install_help() -> Help {
         the absolute local path to the base directory of the SDK to be added. The local version will appear as an \
         installed version of the candidate. The version may not conflict with an existing version, installed or not.".to_string(),
         mnemonic: Some(Mnemonic { shorthand: "i".to_string(), command: "install".to_string() }),
-        exit_code: Some("The subcommand will return a non-zero exit code for versions not found or for an invalid path.".to_string()),
+        exit_code: Some(format!("The subcommand will return a non-zero exit code for versions not found or for an invalid \
+        path. An archive that fails checksum verification, or has no checksum to be verified against, results in an exit code of 65 and is moved to {}.",
+                                "$SDKMAN_DIR/tmp/quarantine".underline())),
         examples: "sdk install java\nsdk install java 17.0.0-tem\nsdk install java 11-local /usr/lib/jvm/java-11-openjdk".to_string(),
         ..Default::default()
     }
@@ -400,16 +508,18 @@ fn list_help() -> Help {
     Help {
         cmd: "sdk list".to_string(),
         tagline: "sdk subcommand to list all candidates or candidate versions".to_string(),
-        synopsis: "sdk list [candidate]".to_string(),
+        synopsis: "sdk list [candidate] [--installed]".to_string(),
         description: format!("Invoke the subcommand without a candidate to see a comprehensive list of all candidates \
         with name, URL, detailed description and an installation command.\nIf the candidate qualifier is specified, \
         the subcommand will display a list of all available and local versions for that candidate. In addition, the \
         version list view marks all versions that are local, installed or currently in use. They appear as follows:\n
 {}
 
-Java has a custom list view with vendor-specific details.", legend.italic()),
+Java has a custom list view with vendor-specific details.\n\nProvide {} to list only the installed versions, sorted \
+from newest to oldest, without contacting the SDKMAN API. The {} version and the version used in this shell are \
+marked as in use, and the former is labelled. Without a candidate, it lists the versions of every installed candidate.", legend.italic(), "--installed".italic(), "default".italic()),
         mnemonic: Some(Mnemonic { shorthand: "ls".to_string(), command: "list".to_string() }),
-        examples: "sdk list\nsdk list java\nsdk list groovy".to_string(),
+        examples: "sdk list\nsdk list java\nsdk list groovy\nsdk list java --installed".to_string(),
         ..Default::default()
     }
 }
@@ -428,6 +538,32 @@ fn selfupdate_help() -> Help {
     }
 }
 
+fn shim_help() -> Help {
+    Help {
+        cmd: "sdk shim".to_string(),
+        tagline: "sdk subcommand to manage the launchers that pick a candidate version per directory".to_string(),
+        synopsis: "sdk shim rehash".to_string(),
+        description: format!("Shim mode is an alternative to switching versions in the shell. The {} directory holds \
+        a launcher for every executable of the installed candidates, which runs the version selected by a session \
+        override such as {}, by {} or {} through a variable such as {}, by the nearest {} or by the {} version, in \
+        that order. Putting the directory on the {} \
+        lets IDEs, cron jobs and non-interactive shells pick the right version without any shell hooks.",
+                             "$SDKMAN_DIR/shims".underline(), "SDKMAN_JAVA_VERSION".italic(), "sdk use".italic(),
+                             "sdk env".italic(), "JAVA_HOME".underline(), ".sdkmanrc".underline(), "default".italic(), "PATH".underline()),
+        subcommands: Some(vec![
+            Subcommand {
+                command: "rehash".to_string(),
+                description: "create the launchers for every executable of the installed versions and remove those \
+                of executables that are gone. Run it after installing or uninstalling a version".to_string(),
+            },
+        ]),
+        exit_code: Some("A launcher returns the exit code of the executable it runs, or 127 if no installed version \
+        provides the executable.".to_string()),
+        examples: "sdk shim rehash\nSDKMAN_JAVA_VERSION=17.0.3-tem java -version".to_string(),
+        ..Default::default()
+    }
+}
+
 fn uninstall_help() -> Help {
     Help {
         cmd: "sdk uninstall".to_string(),
@@ -478,15 +614,16 @@ fn use_help() -> Help {
     Help {
         cmd: "sdk use".to_string(),
         tagline: "sdk subcommand to use a specific version only in the current shell".to_string(),
-        synopsis: "sdk use <candidate> <version>".to_string(),
-        description: "The mandatory candidate and version follow the subcommand to specify what to use in the \
+        synopsis: "sdk use <candidate> <version> [--shell <shell>]".to_string(),
+        description: format!("The mandatory candidate and version follow the subcommand to specify what to use in the \
         shell. This subcommand only operates on the current shell. It does not affect other shells \
         running different versions of the same candidate. It also does not change the default version set for \
-        all subsequent shells.".to_string(),
+        all subsequent shells. The activation code is printed for the shell named in {}, unless {} selects one of \
+        bash, zsh, fish, pwsh or nu.", "$SHELL".underline(), "--shell".italic()),
         mnemonic: Some(Mnemonic { shorthand: "u".to_string(), command: "use".to_string() }),
         exit_code: Some("The subcommand will return a non-zero return code if the candidate or version does not exist."
             .to_string()),
-        examples: "sdk use java 17.0.0-tem".to_string(),
+        examples: "sdk use java 17.0.0-tem\nsdk use java 17.0.0-tem --shell pwsh".to_string(),
         ..Default::default()
     }
 }
@@ -505,6 +642,25 @@ fn version_help() -> Help {
     }
 }
 
+fn which_help() -> Help {
+    let legend = "\
+* - default version
+> - run by the current PATH";
+    Help {
+        cmd: "sdk which".to_string(),
+        tagline: "sdk subcommand to find the candidate versions that provide an executable".to_string(),
+        synopsis: "sdk which <binary> [--all]".to_string(),
+        description: format!("Scan the installed candidate versions for an executable such as {} or {}, and list the \
+        default and the in use versions that provide it, along with its path. Provide {} to also list every other \
+        installed version that has it. The versions are marked as follows:\n
+{}", "jshell".italic(), "mvnd".italic(), "--all".italic(), legend.italic()),
+        exit_code: Some("The subcommand will return a non-zero exit code if no installed version provides the \
+        executable.".to_string()),
+        examples: "sdk which jshell\nsdk which java --all".to_string(),
+        ..Default::default()
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -522,7 +678,6 @@ mod tests {
     /// ```
     ///
     /// See https://insta.rs/ for more details.
-
     fn setup() {
         colored::control::set_override(true);
         colored::control::SHOULD_COLORIZE.set_override(true);
@@ -558,6 +713,12 @@ mod tests {
         insta::assert_snapshot!(render(env_help()));
     }
 
+    #[test]
+    fn should_render_exec_help_with_formatting() {
+        setup();
+        insta::assert_snapshot!(render(exec_help()));
+    }
+
     #[test]
     fn should_render_flush_help_with_formatting() {
         setup();
@@ -570,6 +731,12 @@ mod tests {
         insta::assert_snapshot!(render(home_help()));
     }
 
+    #[test]
+    fn should_render_init_help_with_formatting() {
+        setup();
+        insta::assert_snapshot!(render(init_help()));
+    }
+
     #[test]
     fn should_render_install_help_with_formatting() {
         setup();
@@ -588,6 +755,12 @@ mod tests {
         insta::assert_snapshot!(render(selfupdate_help()));
     }
 
+    #[test]
+    fn should_render_shim_help_with_formatting() {
+        setup();
+        insta::assert_snapshot!(render(shim_help()));
+    }
+
     #[test]
     fn should_render_uninstall_help_with_formatting() {
         setup();
@@ -611,4 +784,10 @@ mod tests {
         setup();
         insta::assert_snapshot!(render(use_help()));
     }
+
+    #[test]
+    fn should_render_which_help_with_formatting() {
+        setup();
+        insta::assert_snapshot!(render(which_help()));
+    }
 }
@@ -3,6 +3,7 @@ use std::process;
 use clap::Parser;
 use colored::Colorize;
 
+use sdkman_cli_native::config::Config;
 use sdkman_cli_native::constants::CANDIDATES_DIR;
 use sdkman_cli_native::helpers::{infer_sdkman_dir, known_candidates, validate_candidate};
 
@@ -24,6 +25,7 @@ fn main() {
     let candidate = args.candidate;
     let version = args.version;
     let sdkman_dir = infer_sdkman_dir();
+    Config::load(&sdkman_dir).apply();
 
     let candidate = validate_candidate(known_candidates(sdkman_dir.to_owned()), &candidate);
 
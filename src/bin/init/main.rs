@@ -0,0 +1,53 @@
+use clap::Parser;
+
+use sdkman_cli_native::activation::{defaults, rewrite_path};
+use sdkman_cli_native::config::Config;
+use sdkman_cli_native::constants::{SDKMAN_DIR_ENV_VAR, SDKMAN_ENV_ENV_VAR, SHIMS_DIR};
+use sdkman_cli_native::helpers::{infer_sdkman_dir, known_candidates};
+use sdkman_cli_native::shell::Shell;
+
+#[derive(Parser, Debug)]
+#[command(
+    bin_name = "sdk init",
+    about = "sdk subcommand to print the code that sets up SDKMAN in a new shell"
+)]
+struct Args {
+    /// Shell to print the code for, detected from $SHELL by default
+    #[arg(value_enum)]
+    shell: Option<Shell>,
+}
+
+// Only reads the current links, so that the code is cheap enough to generate on every startup.
+// The directories are put in front of whatever PATH the shell has, so the output can be cached.
+// A shell started inside a project inherits the SDKMAN_ENV of its parent but not the versions,
+// which the defaults replace, so the marker is dropped for the auto-env hook to activate them.
+fn main() {
+    let args = Args::parse();
+    let sdkman_dir = infer_sdkman_dir();
+    let config = Config::load(&sdkman_dir);
+    config.apply();
+    let shell = args.shell.unwrap_or_else(Shell::detect);
+
+    let candidates = known_candidates(sdkman_dir.to_owned());
+    let (mut bin_dirs, variables) = defaults(&sdkman_dir, "", &candidates);
+    let shims_dir = sdkman_dir.join(SHIMS_DIR);
+    if shims_dir.is_dir() {
+        bin_dirs = rewrite_path(&bin_dirs, &shims_dir, Some(&shims_dir));
+    }
+
+    println!(
+        "{}",
+        shell.export(SDKMAN_DIR_ENV_VAR, &sdkman_dir.to_string_lossy())
+    );
+    if !bin_dirs.is_empty() {
+        println!("{}", shell.prepend_path(&bin_dirs));
+    }
+    for (name, value) in &variables {
+        println!("{}", shell.export(name, value));
+    }
+    println!("{}", shell.unset(SDKMAN_ENV_ENV_VAR));
+    println!("{}", shell.sdk_function());
+    if config.auto_env {
+        println!("{}", shell.auto_env_hook());
+    }
+}
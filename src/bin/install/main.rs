@@ -0,0 +1,136 @@
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::process;
+
+use clap::Parser;
+use colored::Colorize;
+use fs_extra::copy_items;
+use fs_extra::dir::CopyOptions;
+use symlink::symlink_dir;
+use tempfile::Builder;
+
+use sdkman_cli_native::config::Config;
+use sdkman_cli_native::constants::{BIN_DIR, CANDIDATES_DIR, TMP_DIR};
+use sdkman_cli_native::download::{print_progress, DownloadSettings, Downloader};
+use sdkman_cli_native::helpers::{infer_sdkman_dir, known_candidates, validate_candidate};
+use sdkman_cli_native::installer::install_remote;
+
+#[derive(Parser, Debug)]
+#[command(
+    bin_name = "sdk install",
+    about = "sdk subcommand to install a candidate version"
+)]
+struct Args {
+    #[arg(required(true))]
+    candidate: String,
+
+    #[arg(required(true))]
+    version: String,
+
+    #[arg(required(false))]
+    path: Option<String>,
+}
+
+fn main() {
+    let args = Args::parse();
+    let candidate = args.candidate;
+    let version = args.version;
+    let sdkman_dir = infer_sdkman_dir();
+    let config = Config::load(&sdkman_dir);
+    config.apply();
+    let tmp_dir = sdkman_dir.join(TMP_DIR);
+
+    let candidate = validate_candidate(known_candidates(sdkman_dir.to_owned()), &candidate);
+
+    let candidate_path = sdkman_dir.join(CANDIDATES_DIR).join(&candidate);
+    let version_path = candidate_path.join(&version);
+    if version_path.exists() || version_path.is_symlink() {
+        eprintln!(
+            "{} {} is already installed.",
+            candidate.bold(),
+            version.bold()
+        );
+        process::exit(1);
+    }
+
+    match args.path {
+        Some(path) => install_local(&path, &candidate, &version, &version_path, &tmp_dir),
+        None => {
+            let downloader = Downloader::new(DownloadSettings::from_config(&config));
+            println!("downloading {} {}...", candidate.bold(), version.bold());
+            match install_remote(
+                &sdkman_dir,
+                &candidate,
+                &version,
+                &downloader,
+                &mut print_progress,
+            ) {
+                Ok(_) => println!("installed {} {}.", candidate.bold(), version.bold()),
+                Err(e) => {
+                    eprintln!(
+                        "cannot install {} {}: {}",
+                        candidate.bold(),
+                        version.bold(),
+                        e
+                    );
+                    process::exit(e.exit_code());
+                }
+            }
+        }
+    }
+}
+
+fn install_local(path: &str, candidate: &str, version: &str, version_path: &Path, tmp_dir: &Path) {
+    let local_path = validate_local_path(path);
+    let candidate_path = version_path.parent().unwrap();
+
+    fs::create_dir_all(candidate_path).expect("cannot create candidate directory.");
+    symlink_dir(&local_path, version_path).unwrap_or_else(|_| {
+        fs::create_dir_all(tmp_dir).expect("cannot create tmp folder.");
+        let options = CopyOptions::new();
+        let local_path_string = local_path
+            .to_owned()
+            .into_os_string()
+            .into_string()
+            .unwrap();
+        let staging = Builder::new()
+            .prefix(".copy-")
+            .tempdir_in(tmp_dir)
+            .expect("cannot create staging folder.");
+        copy_items(&[local_path_string], staging.path(), &options)
+            .expect("cannot copy to tmp folder.");
+        let tmp_version_path = staging.path().join(local_path.file_name().unwrap());
+        fs::rename(tmp_version_path, version_path).expect("cannot rename copied folder.");
+        let error_message = format!(
+            "cannot create {} symlink, fall back to copy!",
+            version.italic()
+        );
+        println!("{}", error_message.bold())
+    });
+
+    println!(
+        "linked {} {} to {}.",
+        candidate.bold(),
+        version.bold(),
+        local_path.to_str().unwrap()
+    );
+}
+
+fn validate_local_path(path: &str) -> PathBuf {
+    let local_path = match fs::canonicalize(path) {
+        Ok(p) if p.is_dir() => p,
+        _ => {
+            eprintln!("Invalid path! {} is not a directory.", path.bold());
+            process::exit(1);
+        }
+    };
+    if !local_path.join(BIN_DIR).is_dir() {
+        eprintln!(
+            "Invalid path! {} does not contain a {} folder.",
+            path.bold(),
+            BIN_DIR.italic()
+        );
+        process::exit(1);
+    }
+    local_path
+}
@@ -0,0 +1,130 @@
+use std::env;
+use std::path::Path;
+use std::process::{self, Command};
+
+use clap::Parser;
+use colored::Colorize;
+
+use sdkman_cli_native::activation::active_version;
+use sdkman_cli_native::config::Config;
+use sdkman_cli_native::constants::{BIN_DIR, CANDIDATES_DIR, INIT_SCRIPT, SDKMAN_DIR_ENV_VAR};
+use sdkman_cli_native::helpers::{
+    get_current_version, infer_sdkman_dir, installed_versions, known_candidates, validate_candidate,
+};
+
+const LEGEND: &str = "\
++ - local version
+* - installed
+> - currently in use";
+
+#[derive(Parser, Debug)]
+#[command(
+    bin_name = "sdk list",
+    about = "sdk subcommand to list all candidates or candidate versions"
+)]
+struct Args {
+    #[arg(required(false))]
+    candidate: Option<String>,
+
+    /// Only list what is installed, without contacting the SDKMAN API
+    #[arg(long)]
+    installed: bool,
+}
+
+// Versions that are not installed come from the SDKMAN API, which the native list does not
+// call, so it only works offline. Without --installed, the bash implementation lists them.
+fn main() {
+    let args = Args::parse();
+    let sdkman_dir = infer_sdkman_dir();
+    Config::load(&sdkman_dir).apply();
+    if !args.installed {
+        list_online(&sdkman_dir, args.candidate);
+    }
+
+    let all_candidates = known_candidates(sdkman_dir.to_owned());
+    let path = env::var("PATH").unwrap_or_default();
+    let lines = |candidate: &str| {
+        let default = get_current_version(sdkman_dir.to_owned(), candidate);
+        let active = active_version(&sdkman_dir, &path, candidate);
+        installed_versions(sdkman_dir.to_owned(), candidate)
+            .into_iter()
+            .rev()
+            .map(|version| {
+                let local = sdkman_dir
+                    .join(CANDIDATES_DIR)
+                    .join(candidate)
+                    .join(&version)
+                    .is_symlink();
+                let in_use =
+                    active.as_ref() == Some(&version) || default.as_ref() == Some(&version);
+                format!(
+                    " {} {} {}{}",
+                    if in_use { ">" } else { " " },
+                    if local { "+" } else { "*" },
+                    version,
+                    if default.as_ref() == Some(&version) {
+                        " (default)"
+                    } else {
+                        ""
+                    }
+                )
+            })
+            .collect::<Vec<_>>()
+    };
+
+    match args.candidate {
+        Some(candidate) => {
+            let candidate = validate_candidate(all_candidates, &candidate);
+            let lines = lines(&candidate);
+            if lines.is_empty() {
+                println!("No versions of {} are installed.", candidate.bold());
+                return;
+            }
+            println!("{}", format!("Installed {} versions:", candidate).bold());
+            println!("\n{}", lines.join("\n"));
+        }
+        None => {
+            let sections = all_candidates
+                .iter()
+                .map(|candidate| (candidate, lines(candidate)))
+                .filter(|(_, lines)| !lines.is_empty())
+                .map(|(candidate, lines)| format!("{}\n{}", candidate.bold(), lines.join("\n")))
+                .collect::<Vec<_>>();
+            if sections.is_empty() {
+                println!("No candidates are installed.");
+                return;
+            }
+            println!("{}", "Installed candidates:".bold());
+            println!("\n{}", sections.join("\n\n"));
+        }
+    }
+    println!("\n{}", LEGEND.italic());
+}
+
+// Calls the list function of the bash implementation directly, as its `sdk` function would
+// route the command back to this binary.
+fn list_online(sdkman_dir: &Path, candidate: Option<String>) -> ! {
+    let init_script = sdkman_dir.join(BIN_DIR).join(INIT_SCRIPT);
+    if !init_script.is_file() {
+        eprintln!(
+            "The native list only shows installed versions, use {}.",
+            "sdk list --installed".italic()
+        );
+        process::exit(1);
+    }
+    let status = Command::new("bash")
+        .arg("-c")
+        .arg(format!(
+            ". \"${}/{}/{}\" && __sdk_list \"$@\"",
+            SDKMAN_DIR_ENV_VAR, BIN_DIR, INIT_SCRIPT
+        ))
+        .arg("sdk")
+        .args(candidate)
+        .env(SDKMAN_DIR_ENV_VAR, sdkman_dir)
+        .status()
+        .unwrap_or_else(|e| {
+            eprintln!("Failed to run the bash implementation of list: {}", e);
+            process::exit(1);
+        });
+    process::exit(status.code().unwrap_or(1));
+}
@@ -0,0 +1,140 @@
+use std::env;
+use std::env::consts::EXE_SUFFIX;
+use std::path::Path;
+use std::process::{self, Command};
+
+use clap::{Parser, Subcommand};
+use colored::Colorize;
+
+use sdkman_cli_native::activation::{home_variable, rewrite_path};
+use sdkman_cli_native::config::Config;
+use sdkman_cli_native::constants::{CANDIDATES_DIR, SHIMS_DIR};
+use sdkman_cli_native::helpers::{infer_sdkman_dir, known_candidates};
+use sdkman_cli_native::shim::{rehash, resolve, Origin};
+
+const LAUNCHER_NAME: &str = "shim";
+
+#[derive(Parser, Debug)]
+#[command(
+    bin_name = "sdk shim",
+    about = "sdk subcommand to manage the launchers that pick a candidate version per directory"
+)]
+struct Args {
+    #[command(subcommand)]
+    command: ShimCommand,
+}
+
+#[derive(Subcommand, Debug)]
+enum ShimCommand {
+    /// Create a launcher for every executable of the installed versions
+    Rehash,
+}
+
+// The same binary manages the shims and is what every shim links to, telling both apart by the
+// name it was started with. Only the suffix of executables is dropped from it, as names such as
+// runant.py or python3.11 contain dots of their own.
+fn main() {
+    let program = env::args_os().next().unwrap_or_default();
+    let name = Path::new(&program)
+        .file_name()
+        .map(|name| name.to_string_lossy().to_string())
+        .unwrap_or_default();
+    let name = name.strip_suffix(EXE_SUFFIX).unwrap_or(&name).to_string();
+    if name == LAUNCHER_NAME {
+        let args = Args::parse();
+        match args.command {
+            ShimCommand::Rehash => rehash_shims(),
+        }
+    } else {
+        launch(&name);
+    }
+}
+
+fn rehash_shims() {
+    let sdkman_dir = infer_sdkman_dir();
+    Config::load(&sdkman_dir).apply();
+    let launcher = env::current_exe().unwrap_or_else(|e| {
+        eprintln!("cannot locate the shim launcher: {}", e);
+        process::exit(1);
+    });
+    let candidates = known_candidates(sdkman_dir.to_owned());
+    let (created, removed) = rehash(&sdkman_dir, &launcher, &candidates).unwrap_or_else(|e| {
+        eprintln!("cannot rehash shims: {}", e);
+        process::exit(1);
+    });
+    println!(
+        "{} shims created and {} removed in {}.",
+        created.len(),
+        removed.len(),
+        sdkman_dir.join(SHIMS_DIR).display()
+    );
+}
+
+fn launch(name: &str) {
+    let sdkman_dir = infer_sdkman_dir();
+    Config::load(&sdkman_dir).apply();
+    let current_dir = env::current_dir().expect("cannot determine the current directory.");
+    let candidates = known_candidates(sdkman_dir.to_owned());
+    let variable = |name: &str| env::var(name).ok();
+    let target = resolve(&sdkman_dir, &current_dir, name, &variable, &candidates)
+        .unwrap_or_else(|e| {
+            eprintln!("{}", e);
+            process::exit(1);
+        })
+        .unwrap_or_else(|| {
+            eprintln!(
+                "{} is not provided by any installed candidate.",
+                name.bold()
+            );
+            process::exit(127);
+        });
+
+    if !target.executable.is_file() {
+        let origin = match &target.origin {
+            Origin::Override(variable) | Origin::Home(variable) => format!("${}", variable),
+            Origin::Sdkmanrc(path) => path.display().to_string(),
+            Origin::Current => "the current link".to_string(),
+        };
+        eprintln!(
+            "{}",
+            format!(
+                "Stop! {} {} selected by {} is not installed or does not provide {}.",
+                target.candidate, target.version, origin, name
+            )
+            .red()
+        );
+        process::exit(127);
+    }
+
+    // The version's own bin directory goes first, ahead of the shims, so that tools it starts
+    // use the same version. Any other version of the candidate is taken off the PATH.
+    let candidate_dir = sdkman_dir.join(CANDIDATES_DIR).join(&target.candidate);
+    let version_dir = candidate_dir.join(&target.version);
+    let bin_dir = target.executable.parent().unwrap_or(&version_dir);
+    let path = rewrite_path(&env::var("PATH").unwrap_or_default(), &candidate_dir, None);
+    let mut command = Command::new(&target.executable);
+    command
+        .args(env::args_os().skip(1))
+        .env("PATH", rewrite_path(&path, &candidate_dir, Some(bin_dir)))
+        .env(home_variable(&target.candidate), &version_dir);
+    run(command, &target.executable);
+}
+
+#[cfg(unix)]
+fn run(mut command: Command, executable: &Path) {
+    use std::os::unix::process::CommandExt;
+    let e = command.exec();
+    eprintln!("cannot run {}: {}", executable.display(), e);
+    process::exit(126);
+}
+
+#[cfg(not(unix))]
+fn run(mut command: Command, executable: &Path) {
+    match command.status() {
+        Ok(status) => process::exit(status.code().unwrap_or(1)),
+        Err(e) => {
+            eprintln!("cannot run {}: {}", executable.display(), e);
+            process::exit(126);
+        }
+    }
+}
@@ -0,0 +1,49 @@
+use std::env;
+
+use clap::Parser;
+use colored::Colorize;
+
+use sdkman_cli_native::activation::use_version;
+use sdkman_cli_native::config::Config;
+use sdkman_cli_native::helpers::{
+    infer_sdkman_dir, known_candidates, validate_candidate, validate_version_path,
+};
+use sdkman_cli_native::shell::Shell;
+
+#[derive(Parser, Debug)]
+#[command(
+    bin_name = "sdk use",
+    about = "sdk subcommand to use a specific version only in the current shell"
+)]
+struct Args {
+    #[arg(required(true))]
+    candidate: String,
+
+    #[arg(required(true))]
+    version: String,
+
+    /// Shell to print activation code for, detected from $SHELL by default
+    #[arg(long, value_enum)]
+    shell: Option<Shell>,
+}
+
+// Everything but shell code goes to stderr, so that stdout can always be evaluated as is.
+fn main() {
+    let args = Args::parse();
+    let sdkman_dir = infer_sdkman_dir();
+    Config::load(&sdkman_dir).apply();
+    let candidate = validate_candidate(known_candidates(sdkman_dir.to_owned()), &args.candidate);
+    validate_version_path(sdkman_dir.to_owned(), &candidate, &args.version);
+
+    let shell = args.shell.unwrap_or_else(Shell::detect);
+    let path = env::var("PATH").unwrap_or_default();
+    let (path, (home_name, home)) = use_version(&sdkman_dir, &path, &candidate, &args.version);
+
+    eprintln!(
+        "Using {} version {} in this shell.",
+        candidate.bold(),
+        args.version.bold()
+    );
+    println!("{}", shell.export("PATH", &path));
+    println!("{}", shell.export(&home_name, &home));
+}
@@ -1,6 +1,7 @@
 use colored::Colorize;
 
 use sdkman_cli_native::{
+    config::Config,
     constants::VAR_DIR,
     helpers::{check_file_exists, infer_sdkman_dir, read_file_content},
 };
@@ -9,6 +10,7 @@ const NATIVE_VERSION: &str = env!("CARGO_PKG_VERSION");
 
 fn main() {
     let sdkman_dir = infer_sdkman_dir();
+    Config::load(&sdkman_dir).apply();
     let cli_version_file = sdkman_dir.join(VAR_DIR).join(CLI_VERSION_FILE);
     let cli_version = read_file_content(check_file_exists(cli_version_file));
 
@@ -0,0 +1,155 @@
+use std::env;
+use std::path::Path;
+use std::process;
+
+use clap::Parser;
+use colored::Colorize;
+
+use sdkman_cli_native::config::Config;
+use sdkman_cli_native::constants::{BIN_DIR, CANDIDATES_DIR, CURRENT_DIR, SHIMS_DIR};
+use sdkman_cli_native::helpers::{
+    format_table, get_current_version, infer_sdkman_dir, installed_versions, known_candidates,
+};
+use sdkman_cli_native::shim::{find_executable, resolve};
+
+#[derive(Parser, Debug)]
+#[command(
+    bin_name = "sdk which",
+    about = "sdk subcommand to find the candidate versions that provide an executable"
+)]
+struct Args {
+    #[arg(required(true))]
+    binary: String,
+
+    /// Also show versions that are neither the default nor in use
+    #[arg(long)]
+    all: bool,
+}
+
+fn main() {
+    let args = Args::parse();
+    let sdkman_dir = infer_sdkman_dir();
+    Config::load(&sdkman_dir).apply();
+    let candidates = known_candidates(sdkman_dir.to_owned());
+
+    let path = env::var_os("PATH").unwrap_or_default();
+    let on_path = env::split_paths(&path)
+        .filter(|dir| !dir.as_os_str().is_empty())
+        .find_map(|dir| find_executable(&dir, &args.binary));
+    let active = on_path
+        .as_ref()
+        .and_then(|executable| provider(&sdkman_dir, executable, &args.binary, &candidates));
+
+    let mut rows = Vec::new();
+    let mut hidden = 0;
+    for candidate in &candidates {
+        let default = get_current_version(sdkman_dir.to_owned(), candidate);
+        for version in installed_versions(sdkman_dir.to_owned(), candidate) {
+            let bin_dir = sdkman_dir
+                .join(CANDIDATES_DIR)
+                .join(candidate)
+                .join(&version)
+                .join(BIN_DIR);
+            let Some(executable) = find_executable(&bin_dir, &args.binary) else {
+                continue;
+            };
+            let is_default = default.as_ref() == Some(&version);
+            let is_active = active.as_ref() == Some(&(candidate.to_string(), version.to_owned()));
+            if !args.all && !is_default && !is_active {
+                hidden += 1;
+                continue;
+            }
+            let marker = format!(
+                "{}{}",
+                if is_default { "*" } else { " " },
+                if is_active { ">" } else { " " }
+            );
+            rows.push(vec![
+                marker,
+                candidate.to_string(),
+                version,
+                executable.display().to_string(),
+            ]);
+        }
+    }
+
+    if rows.is_empty() && hidden == 0 {
+        eprintln!(
+            "{} is not provided by any installed candidate.",
+            args.binary.bold()
+        );
+        process::exit(1);
+    }
+    let mut notes = Vec::new();
+    if hidden > 0 {
+        notes.push(format!(
+            "{} more installed versions provide {}, use {} to show them.",
+            hidden,
+            args.binary.bold(),
+            "--all".italic()
+        ));
+    }
+    match (&on_path, &active) {
+        (Some(executable), None) => notes.push(format!(
+            "The {} runs {}, which is not managed by SDKMAN.",
+            "PATH".bold(),
+            executable.display()
+        )),
+        (None, _) => notes.push(format!(
+            "{} is not on the {}.",
+            args.binary.bold(),
+            "PATH".bold()
+        )),
+        _ => {}
+    }
+
+    if !rows.is_empty() {
+        print!(
+            "{}",
+            format_table(&["", "CANDIDATE", "VERSION", "PATH"], &rows)
+        );
+        println!();
+    }
+    for note in &notes {
+        println!("{}", note);
+    }
+    if !notes.is_empty() {
+        println!();
+    }
+    println!(
+        "{}",
+        "* - default version\n> - run by the current PATH".italic()
+    );
+}
+
+// Maps an executable found on the PATH back to the candidate version it belongs to, following
+// the current link and, in shim mode, the version the shim would pick for this directory.
+fn provider(
+    sdkman_dir: &Path,
+    executable: &Path,
+    binary: &str,
+    candidates: &[&str],
+) -> Option<(String, String)> {
+    if executable.parent() == Some(&sdkman_dir.join(SHIMS_DIR)) {
+        let current_dir = env::current_dir().ok()?;
+        let variable = |name: &str| env::var(name).ok();
+        let target = resolve(sdkman_dir, &current_dir, binary, &variable, candidates).ok()??;
+        return Some((target.candidate, target.version));
+    }
+    let relative = executable
+        .strip_prefix(sdkman_dir.join(CANDIDATES_DIR))
+        .ok()?;
+    let parts = relative
+        .iter()
+        .map(|part| part.to_string_lossy().to_string())
+        .collect::<Vec<_>>();
+    let [candidate, version, ..] = parts.as_slice() else {
+        return None;
+    };
+    let version = if version == CURRENT_DIR {
+        get_current_version(sdkman_dir.to_path_buf(), candidate)?
+    } else {
+        version.to_owned()
+    };
+    Some((candidate.to_owned(), version))
+}
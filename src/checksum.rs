@@ -0,0 +1,311 @@
+use std::fmt;
+use std::fs::{self, File};
+use std::io::{self, Read};
+use std::path::{Path, PathBuf};
+
+use md5::Md5;
+use sha2::{Digest, Sha256, Sha512};
+
+use crate::constants::QUARANTINE_DIR;
+
+const CHECKSUM_HEADER_PREFIX: &str = "x-sdkman-checksum-";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Algorithm {
+    Sha512,
+    Sha256,
+    Md5,
+}
+
+impl Algorithm {
+    /// All supported algorithms, strongest first.
+    pub const ALL: [Algorithm; 3] = [Algorithm::Sha512, Algorithm::Sha256, Algorithm::Md5];
+
+    pub fn from_name(name: &str) -> Option<Algorithm> {
+        match name.replace('-', "").to_lowercase().as_str() {
+            "sha512" => Some(Algorithm::Sha512),
+            "sha256" => Some(Algorithm::Sha256),
+            "md5" => Some(Algorithm::Md5),
+            _ => None,
+        }
+    }
+
+    /// The file extension of a sidecar checksum file published next to an archive.
+    pub fn extension(&self) -> &'static str {
+        match self {
+            Algorithm::Sha512 => "sha512",
+            Algorithm::Sha256 => "sha256",
+            Algorithm::Md5 => "md5",
+        }
+    }
+}
+
+impl fmt::Display for Algorithm {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let name = match self {
+            Algorithm::Sha512 => "SHA-512",
+            Algorithm::Sha256 => "SHA-256",
+            Algorithm::Md5 => "MD5",
+        };
+        write!(f, "{}", name)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Checksum {
+    pub algorithm: Algorithm,
+    pub expected: String,
+}
+
+#[derive(Debug)]
+pub enum ChecksumError {
+    Mismatch {
+        algorithm: Algorithm,
+        expected: String,
+        actual: String,
+    },
+    Missing,
+    Io(io::Error),
+}
+
+impl fmt::Display for ChecksumError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ChecksumError::Mismatch {
+                algorithm,
+                expected,
+                actual,
+            } => write!(
+                f,
+                "{} checksum mismatch, expected {} but got {}",
+                algorithm, expected, actual
+            ),
+            ChecksumError::Missing => write!(
+                f,
+                "no checksum available to verify the archive, set sdkman_checksum_enable=false to install it unverified"
+            ),
+            ChecksumError::Io(e) => write!(f, "{}", e),
+        }
+    }
+}
+
+impl std::error::Error for ChecksumError {}
+
+impl From<io::Error> for ChecksumError {
+    fn from(e: io::Error) -> Self {
+        ChecksumError::Io(e)
+    }
+}
+
+/// Collects the `X-Sdkman-Checksum-<algorithm>` headers supplied by the broker.
+pub fn checksums_from_headers(headers: &[(String, String)]) -> Vec<Checksum> {
+    headers
+        .iter()
+        .filter_map(|(name, value)| {
+            let name = name.to_lowercase();
+            let algorithm = Algorithm::from_name(name.strip_prefix(CHECKSUM_HEADER_PREFIX)?)?;
+            Some(Checksum {
+                algorithm,
+                expected: normalise(value)?,
+            })
+        })
+        .collect()
+}
+
+/// Parses a sidecar file in the usual `<hex digest>  <file name>` layout.
+pub fn parse_sidecar(algorithm: Algorithm, content: &str) -> Option<Checksum> {
+    let digest = content.split_whitespace().next()?;
+    Some(Checksum {
+        algorithm,
+        expected: normalise(digest)?,
+    })
+}
+
+fn normalise(digest: &str) -> Option<String> {
+    let digest = digest.trim().to_lowercase();
+    (!digest.is_empty() && digest.chars().all(|c| c.is_ascii_hexdigit())).then_some(digest)
+}
+
+pub fn compute(path: &Path, algorithm: Algorithm) -> io::Result<String> {
+    match algorithm {
+        Algorithm::Sha512 => digest_file::<Sha512>(path),
+        Algorithm::Sha256 => digest_file::<Sha256>(path),
+        Algorithm::Md5 => digest_file::<Md5>(path),
+    }
+}
+
+fn digest_file<D: Digest>(path: &Path) -> io::Result<String> {
+    let mut file = File::open(path)?;
+    let mut hasher = D::new();
+    let mut buffer = vec![0; 64 * 1024];
+    loop {
+        let read = file.read(&mut buffer)?;
+        if read == 0 {
+            break;
+        }
+        hasher.update(&buffer[..read]);
+    }
+    Ok(hex::encode(hasher.finalize()))
+}
+
+/// Verifies `path` against every supplied checksum. Without any checksum the archive cannot be
+/// trusted, so verification fails rather than passing by default.
+pub fn verify(path: &Path, checksums: &[Checksum]) -> Result<(), ChecksumError> {
+    if checksums.is_empty() {
+        return Err(ChecksumError::Missing);
+    }
+    for checksum in checksums {
+        let actual = compute(path, checksum.algorithm)?;
+        if actual != checksum.expected {
+            return Err(ChecksumError::Mismatch {
+                algorithm: checksum.algorithm,
+                expected: checksum.expected.to_owned(),
+                actual,
+            });
+        }
+    }
+    Ok(())
+}
+
+/// Moves a rejected archive out of the way into `tmp/quarantine`.
+pub fn quarantine(path: &Path, tmp_dir: &Path) -> io::Result<PathBuf> {
+    let quarantine_dir = tmp_dir.join(QUARANTINE_DIR);
+    fs::create_dir_all(&quarantine_dir)?;
+    let target = quarantine_dir.join(path.file_name().unwrap_or_default());
+    fs::rename(path, &target)?;
+    Ok(target)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::fs;
+
+    use tempfile::TempDir;
+
+    use crate::checksum::{
+        checksums_from_headers, compute, parse_sidecar, quarantine, verify, Algorithm, Checksum,
+        ChecksumError,
+    };
+
+    const CONTENT: &str = "archive-content";
+    const WRONG_SHA256: &str = "bc1f6fc7a2efba4a4c76d4d0bb9d4b0d4e0a16e7b5a3e8b6dfd59c1eb4d1ba1d";
+
+    fn archive(dir: &TempDir) -> std::path::PathBuf {
+        let path = dir.path().join("java-17.zip");
+        fs::write(&path, CONTENT).unwrap();
+        path
+    }
+
+    #[test]
+    fn should_compute_digests_for_all_algorithms() {
+        let dir = TempDir::new().unwrap();
+        let path = dir.path().join("hello.txt");
+        fs::write(&path, "hello").unwrap();
+
+        assert_eq!(
+            compute(&path, Algorithm::Md5).unwrap(),
+            "5d41402abc4b2a76b9719d911017c592"
+        );
+        assert_eq!(
+            compute(&path, Algorithm::Sha256).unwrap(),
+            "2cf24dba5fb0a30e26e83b2ac5b9e29e1b161e5c1fa7425e73043362938b9824"
+        );
+        assert!(compute(&path, Algorithm::Sha512)
+            .unwrap()
+            .starts_with("9b71d224bd62f3785d96d46ad3ea3d73"));
+    }
+
+    #[test]
+    fn should_read_checksums_from_broker_headers() {
+        let headers = vec![
+            ("Content-Type".to_string(), "application/zip".to_string()),
+            (
+                "X-Sdkman-Checksum-SHA-256".to_string(),
+                "ABCDEF".to_string(),
+            ),
+            ("x-sdkman-checksum-md5".to_string(), "0123".to_string()),
+            ("X-Sdkman-Checksum-CRC32".to_string(), "0123".to_string()),
+        ];
+
+        assert_eq!(
+            checksums_from_headers(&headers),
+            vec![
+                Checksum {
+                    algorithm: Algorithm::Sha256,
+                    expected: "abcdef".to_string()
+                },
+                Checksum {
+                    algorithm: Algorithm::Md5,
+                    expected: "0123".to_string()
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn should_parse_sidecar_file() {
+        let checksum = parse_sidecar(Algorithm::Sha256, "ABCD1234  java-17.zip\n").unwrap();
+        assert_eq!(checksum.expected, "abcd1234");
+        assert_eq!(
+            parse_sidecar(Algorithm::Sha256, "<html>not found</html>"),
+            None
+        );
+    }
+
+    #[test]
+    fn should_verify_matching_checksum() {
+        let dir = TempDir::new().unwrap();
+        let path = archive(&dir);
+        let expected = compute(&path, Algorithm::Sha256).unwrap();
+
+        let checksums = [Checksum {
+            algorithm: Algorithm::Sha256,
+            expected,
+        }];
+
+        assert!(verify(&path, &checksums).is_ok());
+    }
+
+    #[test]
+    fn should_reject_mismatching_checksum() {
+        let dir = TempDir::new().unwrap();
+        let path = archive(&dir);
+        let checksums = [Checksum {
+            algorithm: Algorithm::Sha256,
+            expected: WRONG_SHA256.to_string(),
+        }];
+
+        let result = verify(&path, &checksums);
+
+        assert!(matches!(
+            result,
+            Err(ChecksumError::Mismatch {
+                algorithm: Algorithm::Sha256,
+                ..
+            })
+        ));
+    }
+
+    #[test]
+    fn should_reject_archive_without_checksum() {
+        let dir = TempDir::new().unwrap();
+        let path = archive(&dir);
+
+        assert!(matches!(verify(&path, &[]), Err(ChecksumError::Missing)));
+    }
+
+    #[test]
+    fn should_move_rejected_archive_into_quarantine() {
+        let dir = TempDir::new().unwrap();
+        let path = archive(&dir);
+
+        let quarantined = quarantine(&path, dir.path()).unwrap();
+
+        assert!(!path.exists());
+        assert_eq!(
+            quarantined,
+            dir.path().join("quarantine").join("java-17.zip")
+        );
+        assert_eq!(fs::read_to_string(quarantined).unwrap(), CONTENT);
+    }
+}
@@ -0,0 +1,561 @@
+use std::fmt;
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+
+use crate::constants::{CONFIG_FILE, ETC_DIR};
+
+/// The documented default content of `etc/config`.
+pub const DEFAULT_CONFIG: &str = "\
+sdkman_auto_answer=false
+sdkman_auto_complete=true
+sdkman_auto_env=false
+sdkman_auto_update=true
+sdkman_beta_channel=false
+sdkman_checksum_enable=true
+sdkman_colour_enable=true
+sdkman_curl_connect_timeout=7
+sdkman_curl_max_time=10
+sdkman_debug_mode=false
+sdkman_healthcheck_enable=true
+sdkman_insecure_ssl=false
+sdkman_selfupdate_feature=true
+";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ValueKind {
+    Bool,
+    Seconds,
+}
+
+impl fmt::Display for ValueKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ValueKind::Bool => write!(f, "true or false"),
+            ValueKind::Seconds => write!(f, "a whole number of seconds"),
+        }
+    }
+}
+
+/// Every key documented for `etc/config`, in the order of the default file.
+pub const KEYS: [(&str, ValueKind); 13] = [
+    ("sdkman_auto_answer", ValueKind::Bool),
+    ("sdkman_auto_complete", ValueKind::Bool),
+    ("sdkman_auto_env", ValueKind::Bool),
+    ("sdkman_auto_update", ValueKind::Bool),
+    ("sdkman_beta_channel", ValueKind::Bool),
+    ("sdkman_checksum_enable", ValueKind::Bool),
+    ("sdkman_colour_enable", ValueKind::Bool),
+    ("sdkman_curl_connect_timeout", ValueKind::Seconds),
+    ("sdkman_curl_max_time", ValueKind::Seconds),
+    ("sdkman_debug_mode", ValueKind::Bool),
+    ("sdkman_healthcheck_enable", ValueKind::Bool),
+    ("sdkman_insecure_ssl", ValueKind::Bool),
+    ("sdkman_selfupdate_feature", ValueKind::Bool),
+];
+
+pub fn value_kind(key: &str) -> Option<ValueKind> {
+    KEYS.iter()
+        .find(|(name, _)| *name == key)
+        .map(|(_, kind)| *kind)
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Config {
+    pub auto_answer: bool,
+    pub auto_complete: bool,
+    pub auto_env: bool,
+    pub auto_update: bool,
+    pub beta_channel: bool,
+    pub checksum_enable: bool,
+    pub colour_enable: bool,
+    pub curl_connect_timeout: u64,
+    pub curl_max_time: u64,
+    pub debug_mode: bool,
+    pub healthcheck_enable: bool,
+    pub insecure_ssl: bool,
+    pub selfupdate_feature: bool,
+}
+
+impl Default for Config {
+    fn default() -> Self {
+        Config {
+            auto_answer: false,
+            auto_complete: true,
+            auto_env: false,
+            auto_update: true,
+            beta_channel: false,
+            checksum_enable: true,
+            colour_enable: true,
+            curl_connect_timeout: 7,
+            curl_max_time: 10,
+            debug_mode: false,
+            healthcheck_enable: true,
+            insecure_ssl: false,
+            selfupdate_feature: true,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ConfigWarning {
+    UnknownKey {
+        line: usize,
+        key: String,
+    },
+    InvalidValue {
+        line: usize,
+        key: String,
+        value: String,
+        expected: ValueKind,
+    },
+    Malformed {
+        line: usize,
+        content: String,
+    },
+}
+
+impl fmt::Display for ConfigWarning {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ConfigWarning::UnknownKey { line, key } => {
+                write!(
+                    f,
+                    "line {}: {}",
+                    line,
+                    SettingError::UnknownKey(key.to_owned())
+                )
+            }
+            ConfigWarning::InvalidValue {
+                line,
+                key,
+                value,
+                expected,
+            } => {
+                let error = SettingError::InvalidValue {
+                    key: key.to_owned(),
+                    value: value.to_owned(),
+                    expected: *expected,
+                };
+                write!(f, "line {}: {}", line, error)
+            }
+            ConfigWarning::Malformed { line, content } => {
+                write!(
+                    f,
+                    "line {}: expected key=value but found '{}'",
+                    line, content
+                )
+            }
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum SettingError {
+    UnknownKey(String),
+    InvalidValue {
+        key: String,
+        value: String,
+        expected: ValueKind,
+    },
+}
+
+impl fmt::Display for SettingError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            SettingError::UnknownKey(key) => write!(f, "unknown key {}", key),
+            SettingError::InvalidValue {
+                key,
+                value,
+                expected,
+            } => write!(
+                f,
+                "invalid value '{}' for {}, expected {}",
+                value, key, expected
+            ),
+        }
+    }
+}
+
+impl std::error::Error for SettingError {}
+
+pub fn config_path(sdkman_dir: &Path) -> PathBuf {
+    sdkman_dir.join(ETC_DIR).join(CONFIG_FILE)
+}
+
+impl Config {
+    /// Parses the content of `etc/config`. Keys that are missing keep their defaults; unknown
+    /// keys and bad values are reported and otherwise ignored.
+    pub fn parse(content: &str) -> (Config, Vec<ConfigWarning>) {
+        let mut config = Config::default();
+        let mut warnings = Vec::new();
+        for (index, raw) in content.lines().enumerate() {
+            let line = index + 1;
+            let trimmed = raw.trim();
+            if trimmed.is_empty() || trimmed.starts_with('#') {
+                continue;
+            }
+            let Some((key, value)) = trimmed.split_once('=') else {
+                warnings.push(ConfigWarning::Malformed {
+                    line,
+                    content: trimmed.to_string(),
+                });
+                continue;
+            };
+            match config.set(key.trim(), value.trim()) {
+                Ok(()) => {}
+                Err(SettingError::UnknownKey(key)) => {
+                    warnings.push(ConfigWarning::UnknownKey { line, key })
+                }
+                Err(SettingError::InvalidValue {
+                    key,
+                    value,
+                    expected,
+                }) => warnings.push(ConfigWarning::InvalidValue {
+                    line,
+                    key,
+                    value,
+                    expected,
+                }),
+            }
+        }
+        (config, warnings)
+    }
+
+    /// Reads `etc/config`, treating a missing file as the documented defaults.
+    pub fn read(sdkman_dir: &Path) -> io::Result<(Config, Vec<ConfigWarning>)> {
+        match fs::read_to_string(config_path(sdkman_dir)) {
+            Ok(content) => Ok(Config::parse(&content)),
+            Err(e) if e.kind() == io::ErrorKind::NotFound => Ok((Config::default(), Vec::new())),
+            Err(e) => Err(e),
+        }
+    }
+
+    /// Loads the settings shared by all native subcommands. Problems in the file only surface
+    /// in debug mode, so that a typo never stops a subcommand from running.
+    pub fn load(sdkman_dir: &Path) -> Config {
+        let (config, warnings) = Config::read(sdkman_dir).unwrap_or_default();
+        if config.debug_mode {
+            for warning in warnings {
+                eprintln!("{}: {}", config_path(sdkman_dir).display(), warning);
+            }
+        }
+        config
+    }
+
+    /// Applies the settings that affect how every subcommand renders its output.
+    pub fn apply(&self) {
+        if !self.colour_enable {
+            colored::control::set_override(false);
+        }
+    }
+
+    pub fn get(&self, key: &str) -> Option<String> {
+        let value = match key {
+            "sdkman_auto_answer" => self.auto_answer.to_string(),
+            "sdkman_auto_complete" => self.auto_complete.to_string(),
+            "sdkman_auto_env" => self.auto_env.to_string(),
+            "sdkman_auto_update" => self.auto_update.to_string(),
+            "sdkman_beta_channel" => self.beta_channel.to_string(),
+            "sdkman_checksum_enable" => self.checksum_enable.to_string(),
+            "sdkman_colour_enable" => self.colour_enable.to_string(),
+            "sdkman_curl_connect_timeout" => self.curl_connect_timeout.to_string(),
+            "sdkman_curl_max_time" => self.curl_max_time.to_string(),
+            "sdkman_debug_mode" => self.debug_mode.to_string(),
+            "sdkman_healthcheck_enable" => self.healthcheck_enable.to_string(),
+            "sdkman_insecure_ssl" => self.insecure_ssl.to_string(),
+            "sdkman_selfupdate_feature" => self.selfupdate_feature.to_string(),
+            _ => return None,
+        };
+        Some(value)
+    }
+
+    /// Sets a single key from its textual value, failing for unknown keys and values that do
+    /// not match the key's type.
+    pub fn set(&mut self, key: &str, value: &str) -> Result<(), SettingError> {
+        let kind = value_kind(key).ok_or_else(|| SettingError::UnknownKey(key.to_string()))?;
+        let invalid = || SettingError::InvalidValue {
+            key: key.to_string(),
+            value: value.to_string(),
+            expected: kind,
+        };
+        match kind {
+            ValueKind::Bool => {
+                let flag = value.parse::<bool>().map_err(|_| invalid())?;
+                let field = match key {
+                    "sdkman_auto_answer" => &mut self.auto_answer,
+                    "sdkman_auto_complete" => &mut self.auto_complete,
+                    "sdkman_auto_env" => &mut self.auto_env,
+                    "sdkman_auto_update" => &mut self.auto_update,
+                    "sdkman_beta_channel" => &mut self.beta_channel,
+                    "sdkman_checksum_enable" => &mut self.checksum_enable,
+                    "sdkman_colour_enable" => &mut self.colour_enable,
+                    "sdkman_debug_mode" => &mut self.debug_mode,
+                    "sdkman_healthcheck_enable" => &mut self.healthcheck_enable,
+                    "sdkman_insecure_ssl" => &mut self.insecure_ssl,
+                    "sdkman_selfupdate_feature" => &mut self.selfupdate_feature,
+                    _ => unreachable!("{} is not a boolean key", key),
+                };
+                *field = flag;
+            }
+            ValueKind::Seconds => {
+                let seconds = value.parse::<u64>().map_err(|_| invalid())?;
+                let field = match key {
+                    "sdkman_curl_connect_timeout" => &mut self.curl_connect_timeout,
+                    "sdkman_curl_max_time" => &mut self.curl_max_time,
+                    _ => unreachable!("{} is not a key in seconds", key),
+                };
+                *field = seconds;
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Where the effective value of a key comes from.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Source {
+    Default,
+    File,
+}
+
+impl fmt::Display for Source {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Source::Default => write!(f, "default"),
+            Source::File => write!(f, "file"),
+        }
+    }
+}
+
+fn assigned_key(line: &str) -> Option<&str> {
+    let trimmed = line.trim();
+    if trimmed.starts_with('#') {
+        return None;
+    }
+    trimmed.split_once('=').map(|(key, _)| key.trim())
+}
+
+/// Returns the value the file assigns to `key`, ignoring assignments that do not validate.
+/// As with [`Config::parse`], the last valid assignment wins.
+pub fn file_value(content: &str, key: &str) -> Option<String> {
+    content
+        .lines()
+        .filter(|line| assigned_key(line) == Some(key))
+        .filter_map(|line| line.split_once('=').map(|(_, value)| value.trim()))
+        .rfind(|value| Config::default().set(key, value).is_ok())
+        .map(str::to_string)
+}
+
+/// Resolves the effective value of every documented key together with its source.
+pub fn effective_values(content: &str) -> Vec<(&'static str, String, Source)> {
+    let defaults = Config::default();
+    KEYS.iter()
+        .map(|(key, _)| match file_value(content, key) {
+            Some(value) => (*key, value, Source::File),
+            None => (*key, defaults.get(key).unwrap_or_default(), Source::Default),
+        })
+        .collect()
+}
+
+/// Assigns `key` in the content of `etc/config`. The first assignment is rewritten in place and
+/// later duplicates are dropped; a missing key is appended. Comments, blank lines and the order
+/// of all other keys are kept.
+pub fn set_value(content: &str, key: &str, value: &str) -> Result<String, SettingError> {
+    Config::default().set(key, value)?;
+    let assignment = format!("{}={}", key, value);
+    let mut assigned = false;
+    let mut lines = Vec::new();
+    for line in content.lines() {
+        if assigned_key(line) != Some(key) {
+            lines.push(line);
+        } else if !assigned {
+            lines.push(&assignment);
+            assigned = true;
+        }
+    }
+    if !assigned {
+        lines.push(&assignment);
+    }
+    Ok(join_lines(&lines))
+}
+
+/// Removes every assignment of `key` so that the documented default applies again.
+pub fn unset_value(content: &str, key: &str) -> Result<String, SettingError> {
+    if value_kind(key).is_none() {
+        return Err(SettingError::UnknownKey(key.to_string()));
+    }
+    let lines = content
+        .lines()
+        .filter(|line| assigned_key(line) != Some(key))
+        .collect::<Vec<_>>();
+    Ok(join_lines(&lines))
+}
+
+fn join_lines(lines: &[&str]) -> String {
+    lines.iter().map(|line| format!("{}\n", line)).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use std::fs;
+
+    use tempfile::TempDir;
+
+    use crate::config::{
+        effective_values, set_value, unset_value, Config, ConfigWarning, SettingError, Source,
+        ValueKind, DEFAULT_CONFIG, KEYS,
+    };
+
+    #[test]
+    fn should_parse_documented_defaults() {
+        let (config, warnings) = Config::parse(DEFAULT_CONFIG);
+        assert_eq!(config, Config::default());
+        assert!(warnings.is_empty());
+    }
+
+    #[test]
+    fn should_expose_every_documented_key() {
+        let config = Config::default();
+        for (key, _) in KEYS {
+            assert!(config.get(key).is_some(), "missing {}", key);
+            assert!(DEFAULT_CONFIG.contains(&format!("{}=", key)));
+        }
+    }
+
+    #[test]
+    fn should_set_every_documented_key_on_its_own_field() {
+        for (key, kind) in KEYS {
+            let value = match kind {
+                ValueKind::Bool => {
+                    (!Config::default().get(key).unwrap().parse::<bool>().unwrap()).to_string()
+                }
+                ValueKind::Seconds => "42".to_string(),
+            };
+            let mut config = Config::default();
+            config.set(key, &value).unwrap();
+
+            assert_eq!(config.get(key), Some(value), "wrong field for {}", key);
+            let changed = KEYS
+                .iter()
+                .filter(|(other, _)| config.get(other) != Config::default().get(other))
+                .count();
+            assert_eq!(changed, 1, "{} changed other keys", key);
+        }
+    }
+
+    #[test]
+    fn should_parse_values_around_comments_and_whitespace() {
+        let content = "# my settings\n\nsdkman_auto_answer = true\nsdkman_curl_max_time=30\r\n";
+        let (config, warnings) = Config::parse(content);
+
+        assert!(config.auto_answer);
+        assert_eq!(config.curl_max_time, 30);
+        assert!(config.colour_enable);
+        assert!(warnings.is_empty());
+    }
+
+    #[test]
+    fn should_report_unknown_and_invalid_keys_with_line_numbers() {
+        let content =
+            "sdkman_auto_answer=true\nsdkman_colour_enabel=false\nsdkman_debug_mode=yes\n\
+                       sdkman_curl_max_time=-1\nnonsense\n";
+        let (config, warnings) = Config::parse(content);
+
+        assert!(config.auto_answer);
+        assert!(!config.debug_mode);
+        assert_eq!(config.curl_max_time, 10);
+        assert_eq!(
+            warnings,
+            vec![
+                ConfigWarning::UnknownKey {
+                    line: 2,
+                    key: "sdkman_colour_enabel".to_string()
+                },
+                ConfigWarning::InvalidValue {
+                    line: 3,
+                    key: "sdkman_debug_mode".to_string(),
+                    value: "yes".to_string(),
+                    expected: ValueKind::Bool
+                },
+                ConfigWarning::InvalidValue {
+                    line: 4,
+                    key: "sdkman_curl_max_time".to_string(),
+                    value: "-1".to_string(),
+                    expected: ValueKind::Seconds
+                },
+                ConfigWarning::Malformed {
+                    line: 5,
+                    content: "nonsense".to_string()
+                },
+            ]
+        );
+        assert_eq!(
+            warnings[2].to_string(),
+            "line 4: invalid value '-1' for sdkman_curl_max_time, expected a whole number of seconds"
+        );
+    }
+
+    #[test]
+    fn should_fall_back_to_defaults_without_config_file() {
+        let dir = TempDir::new().unwrap();
+        let (config, warnings) = Config::read(dir.path()).unwrap();
+        assert_eq!(config, Config::default());
+        assert!(warnings.is_empty());
+    }
+
+    #[test]
+    fn should_read_config_file_from_etc() {
+        let dir = TempDir::new().unwrap();
+        fs::create_dir_all(dir.path().join("etc")).unwrap();
+        fs::write(dir.path().join("etc/config"), "sdkman_insecure_ssl=true\n").unwrap();
+
+        assert!(Config::load(dir.path()).insecure_ssl);
+    }
+
+    #[test]
+    fn should_set_value_in_place_keeping_comments_and_order() {
+        let content =
+            "# tuned for CI\nsdkman_auto_answer=false\n\nsdkman_curl_max_time=10\nsdkman_auto_answer=false\n";
+        let updated = set_value(content, "sdkman_auto_answer", "true").unwrap();
+        assert_eq!(
+            updated,
+            "# tuned for CI\nsdkman_auto_answer=true\n\nsdkman_curl_max_time=10\n"
+        );
+
+        let appended = set_value(&updated, "sdkman_debug_mode", "true").unwrap();
+        assert!(appended.ends_with("sdkman_curl_max_time=10\nsdkman_debug_mode=true\n"));
+    }
+
+    #[test]
+    fn should_reject_invalid_settings() {
+        assert_eq!(
+            set_value("", "sdkman_curl_max_time", "soon"),
+            Err(SettingError::InvalidValue {
+                key: "sdkman_curl_max_time".to_string(),
+                value: "soon".to_string(),
+                expected: ValueKind::Seconds
+            })
+        );
+        assert_eq!(
+            unset_value("", "sdkman_colour"),
+            Err(SettingError::UnknownKey("sdkman_colour".to_string()))
+        );
+    }
+
+    #[test]
+    fn should_unset_value_and_report_sources() {
+        let content = "sdkman_auto_env=true\n# sdkman_auto_env=false\nsdkman_debug_mode=maybe\n";
+        let values = effective_values(content);
+        assert!(values.contains(&("sdkman_auto_env", "true".to_string(), Source::File)));
+        assert!(values.contains(&("sdkman_debug_mode", "false".to_string(), Source::Default)));
+
+        let updated = unset_value(content, "sdkman_auto_env").unwrap();
+        assert_eq!(
+            updated,
+            "# sdkman_auto_env=false\nsdkman_debug_mode=maybe\n"
+        );
+        let values = effective_values(&updated);
+        assert!(values.contains(&("sdkman_auto_env", "false".to_string(), Source::Default)));
+    }
+}
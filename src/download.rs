@@ -0,0 +1,741 @@
+use std::fmt;
+use std::fs::{self, OpenOptions};
+use std::io::{self, Read, Write};
+use std::path::{Path, PathBuf};
+use std::thread;
+use std::time::Duration;
+
+use ureq::http::{Response, StatusCode};
+use ureq::tls::TlsConfig;
+use ureq::{Agent, Body};
+
+use crate::config::Config;
+use crate::constants::{
+    CANDIDATES_API_ENV_VAR, DEFAULT_CANDIDATES_API, PARTIAL_DOWNLOAD_EXTENSION,
+};
+
+const MAX_REDIRECTS: u32 = 10;
+const BUFFER_SIZE: usize = 64 * 1024;
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct DownloadSettings {
+    pub broker_url: String,
+    pub connect_timeout: Duration,
+    pub max_time: Duration,
+    pub insecure_ssl: bool,
+    pub checksum_enable: bool,
+    pub retries: u32,
+    pub backoff: Duration,
+}
+
+impl Default for DownloadSettings {
+    fn default() -> Self {
+        DownloadSettings {
+            broker_url: DEFAULT_CANDIDATES_API.to_string(),
+            connect_timeout: Duration::from_secs(7),
+            max_time: Duration::from_secs(10),
+            insecure_ssl: false,
+            checksum_enable: true,
+            retries: 3,
+            backoff: Duration::from_secs(1),
+        }
+    }
+}
+
+impl DownloadSettings {
+    /// Derives the settings from `etc/config` and the broker URL from the environment.
+    pub fn load(sdkman_dir: &Path) -> DownloadSettings {
+        DownloadSettings::from_config(&Config::load(sdkman_dir))
+    }
+
+    pub fn from_config(config: &Config) -> DownloadSettings {
+        let defaults = DownloadSettings::default();
+        DownloadSettings {
+            broker_url: std::env::var(CANDIDATES_API_ENV_VAR)
+                .ok()
+                .filter(|url| !url.trim().is_empty())
+                .unwrap_or(defaults.broker_url),
+            connect_timeout: Duration::from_secs(config.curl_connect_timeout),
+            max_time: Duration::from_secs(config.curl_max_time),
+            insecure_ssl: config.insecure_ssl,
+            checksum_enable: config.checksum_enable,
+            ..defaults
+        }
+    }
+}
+
+#[derive(Debug)]
+pub enum DownloadError {
+    Status(String, u16),
+    TooManyRedirects(String),
+    Transport(String, String),
+    Incomplete {
+        url: String,
+        expected: u64,
+        actual: u64,
+    },
+    Io(io::Error),
+}
+
+impl fmt::Display for DownloadError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            DownloadError::Status(url, status) => {
+                write!(f, "server responded with status {} for {}", status, url)
+            }
+            DownloadError::TooManyRedirects(url) => write!(f, "too many redirects for {}", url),
+            DownloadError::Transport(url, reason) => write!(f, "cannot reach {}: {}", url, reason),
+            DownloadError::Incomplete {
+                url,
+                expected,
+                actual,
+            } => write!(
+                f,
+                "expected {} bytes from {} but received {}",
+                expected, url, actual
+            ),
+            DownloadError::Io(e) => write!(f, "{}", e),
+        }
+    }
+}
+
+impl std::error::Error for DownloadError {}
+
+impl From<io::Error> for DownloadError {
+    fn from(e: io::Error) -> Self {
+        DownloadError::Io(e)
+    }
+}
+
+impl DownloadError {
+    fn is_retryable(&self) -> bool {
+        match self {
+            DownloadError::Status(_, status) => *status == 429 || *status >= 500,
+            DownloadError::Transport(_, _) | DownloadError::Incomplete { .. } => true,
+            DownloadError::TooManyRedirects(_) | DownloadError::Io(_) => false,
+        }
+    }
+}
+
+/// A completed download, along with the headers of every response in the redirect chain.
+#[derive(Debug)]
+pub struct Download {
+    pub path: PathBuf,
+    pub size: u64,
+    pub url: String,
+    pub headers: Vec<(String, String)>,
+}
+
+impl Download {
+    pub fn header(&self, name: &str) -> Option<&str> {
+        self.headers
+            .iter()
+            .find(|(key, _)| key.eq_ignore_ascii_case(name))
+            .map(|(_, value)| value.as_str())
+    }
+}
+
+struct Followed {
+    response: Response<Body>,
+    url: String,
+    headers: Vec<(String, String)>,
+}
+
+pub struct Downloader {
+    agent: Agent,
+    settings: DownloadSettings,
+}
+
+impl Downloader {
+    pub fn new(settings: DownloadSettings) -> Downloader {
+        let config = Agent::config_builder()
+            .timeout_connect(Some(settings.connect_timeout))
+            .tls_config(
+                TlsConfig::builder()
+                    .disable_verification(settings.insecure_ssl)
+                    .build(),
+            )
+            .http_status_as_error(false)
+            .max_redirects(0)
+            .build();
+        Downloader {
+            agent: Agent::new_with_config(config),
+            settings,
+        }
+    }
+
+    pub fn settings(&self) -> &DownloadSettings {
+        &self.settings
+    }
+
+    pub fn archive_url(&self, candidate: &str, version: &str, platform: &str) -> String {
+        format!(
+            "{}/broker/download/{}/{}/{}",
+            self.settings.broker_url.trim_end_matches('/'),
+            candidate,
+            version,
+            platform
+        )
+    }
+
+    /// Fetches a small text resource, bounded by `sdkman_curl_max_time`.
+    pub fn fetch_text(&self, url: &str) -> Result<String, DownloadError> {
+        self.with_retries(|| {
+            let followed = self.follow(url, Some(self.settings.max_time), 0)?;
+            let mut text = String::new();
+            followed
+                .response
+                .into_body()
+                .into_reader()
+                .read_to_string(&mut text)
+                .map_err(|e| DownloadError::Transport(url.to_string(), e.to_string()))?;
+            Ok(text)
+        })
+    }
+
+    /// Streams `url` into `destination`, reporting the bytes received so far and the expected
+    /// total to `progress`.
+    ///
+    /// Bytes are written to a `.part` file next to `destination`, which survives a failed
+    /// attempt and is resumed with a range request on the next one. The file is only moved to
+    /// `destination` once its length matches what the server announced.
+    pub fn download(
+        &self,
+        url: &str,
+        destination: &Path,
+        progress: &mut dyn FnMut(u64, Option<u64>),
+    ) -> Result<Download, DownloadError> {
+        let partial = partial_path(destination);
+        let download = self.with_retries(|| {
+            let offset = fs::metadata(&partial).map(|m| m.len()).unwrap_or(0);
+            let followed = match self.follow(url, None, offset) {
+                Err(DownloadError::Status(_, 416)) if offset > 0 => {
+                    fs::remove_file(&partial)?;
+                    self.follow(url, None, 0)?
+                }
+                result => result?,
+            };
+            let Followed {
+                response,
+                url: final_url,
+                headers,
+            } = followed;
+
+            let resumed = offset > 0 && response.status() == StatusCode::PARTIAL_CONTENT;
+            let length = response.body().content_length();
+            let (mut size, expected) = if resumed {
+                let total = content_range_total(&response).or(length.map(|l| l + offset));
+                (offset, total)
+            } else {
+                (0, length)
+            };
+            let mut file = OpenOptions::new()
+                .create(true)
+                .write(true)
+                .append(resumed)
+                .truncate(!resumed)
+                .open(&partial)?;
+            let mut reader = response.into_body().into_reader();
+            let mut buffer = vec![0; BUFFER_SIZE];
+            progress(size, expected);
+            loop {
+                let read = reader
+                    .read(&mut buffer)
+                    .map_err(|e| DownloadError::Transport(final_url.to_owned(), e.to_string()))?;
+                if read == 0 {
+                    break;
+                }
+                file.write_all(&buffer[..read])?;
+                size += read as u64;
+                progress(size, expected);
+            }
+            file.flush()?;
+
+            match expected {
+                Some(expected) if expected != size => Err(DownloadError::Incomplete {
+                    url: final_url,
+                    expected,
+                    actual: size,
+                }),
+                _ => Ok(Download {
+                    path: destination.to_path_buf(),
+                    size,
+                    url: final_url,
+                    headers,
+                }),
+            }
+        })?;
+        fs::rename(&partial, destination)?;
+
+        Ok(download)
+    }
+
+    fn follow(
+        &self,
+        url: &str,
+        max_time: Option<Duration>,
+        offset: u64,
+    ) -> Result<Followed, DownloadError> {
+        let mut current = url.to_string();
+        let mut headers = Vec::new();
+        for _ in 0..=MAX_REDIRECTS {
+            let mut request = self.agent.get(&current);
+            if offset > 0 {
+                request = request.header("Range", format!("bytes={}-", offset));
+            }
+            let response = request
+                .config()
+                .timeout_global(max_time)
+                .build()
+                .call()
+                .map_err(|e| DownloadError::Transport(current.to_owned(), e.to_string()))?;
+            headers.extend(response.headers().iter().map(|(name, value)| {
+                (
+                    name.as_str().to_string(),
+                    value.to_str().unwrap_or_default().to_string(),
+                )
+            }));
+
+            let status = response.status();
+            let location = response
+                .headers()
+                .get("location")
+                .and_then(|v| v.to_str().ok());
+            match location {
+                Some(location) if status.is_redirection() => {
+                    current = resolve_location(&current, location);
+                }
+                _ if status.is_success() => {
+                    return Ok(Followed {
+                        response,
+                        url: current,
+                        headers,
+                    })
+                }
+                _ => return Err(DownloadError::Status(current, status.as_u16())),
+            }
+        }
+        Err(DownloadError::TooManyRedirects(url.to_string()))
+    }
+
+    fn with_retries<T>(
+        &self,
+        mut attempt: impl FnMut() -> Result<T, DownloadError>,
+    ) -> Result<T, DownloadError> {
+        let mut retries = 0;
+        loop {
+            match attempt() {
+                Err(e) if e.is_retryable() && retries < self.settings.retries => {
+                    thread::sleep(self.settings.backoff * 2u32.pow(retries));
+                    retries += 1;
+                }
+                result => return result,
+            }
+        }
+    }
+}
+
+// Parses the complete length out of a `Content-Range: bytes <start>-<end>/<total>` header.
+fn content_range_total(response: &Response<Body>) -> Option<u64> {
+    response
+        .headers()
+        .get("content-range")?
+        .to_str()
+        .ok()?
+        .rsplit('/')
+        .next()?
+        .trim()
+        .parse()
+        .ok()
+}
+
+/// The file that holds the bytes of an unfinished download of `destination`.
+pub fn partial_path(destination: &Path) -> PathBuf {
+    let mut file_name = destination.file_name().unwrap_or_default().to_os_string();
+    file_name.push(".");
+    file_name.push(PARTIAL_DOWNLOAD_EXTENSION);
+    destination.with_file_name(file_name)
+}
+
+/// Lists the partial downloads left behind in `tmp_dir` by interrupted downloads.
+pub fn partial_downloads(tmp_dir: &Path) -> Vec<PathBuf> {
+    fs::read_dir(tmp_dir)
+        .map(|entries| {
+            entries
+                .filter_map(|entry| entry.ok().map(|e| e.path()))
+                .filter(|path| {
+                    path.is_file()
+                        && path.extension().and_then(|e| e.to_str())
+                            == Some(PARTIAL_DOWNLOAD_EXTENSION)
+                })
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
+fn resolve_location(current: &str, location: &str) -> String {
+    if location.contains("://") {
+        return location.to_string();
+    }
+    let scheme_end = current.find("://").map(|i| i + 3).unwrap_or(0);
+    let authority_end = current[scheme_end..]
+        .find('/')
+        .map(|i| scheme_end + i)
+        .unwrap_or(current.len());
+    if location.starts_with('/') {
+        format!("{}{}", &current[..authority_end], location)
+    } else {
+        let base_end = current.rfind('/').filter(|i| *i >= authority_end);
+        match base_end {
+            Some(i) => format!("{}/{}", &current[..i], location),
+            None => format!("{}/{}", &current[..authority_end], location),
+        }
+    }
+}
+
+/// Renders a single-line progress indicator on stderr.
+pub fn print_progress(downloaded: u64, total: Option<u64>) {
+    const WIDTH: u64 = 40;
+    match total.filter(|t| *t > 0) {
+        Some(total) => {
+            let filled = (downloaded.min(total) * WIDTH / total) as usize;
+            eprint!(
+                "\r[{}{}] {:>5.1}%",
+                "#".repeat(filled),
+                " ".repeat(WIDTH as usize - filled),
+                downloaded as f64 * 100.0 / total as f64
+            );
+            if downloaded >= total {
+                eprintln!();
+            }
+        }
+        None => eprint!("\r{} KiB", downloaded / 1024),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::fs;
+    use std::io::{Read, Write};
+    use std::net::TcpListener;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use std::sync::Arc;
+    use std::thread;
+    use std::time::Duration;
+
+    use serial_test::serial;
+    use tempfile::TempDir;
+    use tiny_http::{Header, Request, Response, Server};
+
+    use crate::constants::CANDIDATES_API_ENV_VAR;
+    use crate::download::{
+        partial_downloads, partial_path, resolve_location, DownloadError, DownloadSettings,
+        Downloader,
+    };
+
+    fn serve<F>(handler: F) -> String
+    where
+        F: Fn(Request) + Send + 'static,
+    {
+        let server = Server::http("127.0.0.1:0").unwrap();
+        let url = format!("http://{}", server.server_addr().to_ip().unwrap());
+        thread::spawn(move || {
+            for request in server.incoming_requests() {
+                handler(request);
+            }
+        });
+        url
+    }
+
+    fn settings(broker_url: &str) -> DownloadSettings {
+        DownloadSettings {
+            broker_url: broker_url.to_string(),
+            backoff: Duration::from_millis(1),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn should_download_archive_and_report_progress() {
+        let url = serve(|request| {
+            request
+                .respond(Response::from_string("archive-content"))
+                .unwrap()
+        });
+        let dir = TempDir::new().unwrap();
+        let destination = dir.path().join("java-17.bin");
+        let mut reported = Vec::new();
+
+        let download = Downloader::new(settings(&url))
+            .download(
+                &format!("{}/java", url),
+                &destination,
+                &mut |done, total| reported.push((done, total)),
+            )
+            .unwrap();
+
+        assert_eq!(fs::read_to_string(&destination).unwrap(), "archive-content");
+        assert_eq!(download.size, 15);
+        assert_eq!(reported.first(), Some(&(0, Some(15))));
+        assert_eq!(reported.last(), Some(&(15, Some(15))));
+    }
+
+    #[test]
+    fn should_follow_redirects_and_keep_broker_headers() {
+        let url = serve(|request| {
+            let response = if request.url().starts_with("/broker") {
+                Response::from_string("")
+                    .with_status_code(302)
+                    .with_header(Header::from_bytes("Location", "/files/java-17.tar.gz").unwrap())
+                    .with_header(Header::from_bytes("X-Sdkman-ArchiveType", "tar").unwrap())
+            } else {
+                Response::from_string("tarball")
+            };
+            request.respond(response).unwrap()
+        });
+        let dir = TempDir::new().unwrap();
+        let downloader = Downloader::new(settings(&url));
+        let archive_url = downloader.archive_url("java", "17.0.3-tem", "linuxx64");
+
+        let download = downloader
+            .download(&archive_url, &dir.path().join("java.bin"), &mut |_, _| {})
+            .unwrap();
+
+        assert_eq!(download.url, format!("{}/files/java-17.tar.gz", url));
+        assert_eq!(download.header("x-sdkman-archivetype"), Some("tar"));
+        assert_eq!(
+            fs::read_to_string(dir.path().join("java.bin")).unwrap(),
+            "tarball"
+        );
+    }
+
+    #[test]
+    fn should_retry_server_errors_with_backoff() {
+        let calls = Arc::new(AtomicUsize::new(0));
+        let counter = calls.clone();
+        let url = serve(move |request| {
+            let response = if counter.fetch_add(1, Ordering::SeqCst) < 2 {
+                Response::from_string("busy").with_status_code(503)
+            } else {
+                Response::from_string("ok")
+            };
+            request.respond(response).unwrap()
+        });
+
+        let text = Downloader::new(settings(&url)).fetch_text(&url).unwrap();
+
+        assert_eq!(text, "ok");
+        assert_eq!(calls.load(Ordering::SeqCst), 3);
+    }
+
+    #[test]
+    fn should_not_retry_client_errors() {
+        let calls = Arc::new(AtomicUsize::new(0));
+        let counter = calls.clone();
+        let url = serve(move |request| {
+            counter.fetch_add(1, Ordering::SeqCst);
+            request
+                .respond(Response::from_string("missing").with_status_code(404))
+                .unwrap()
+        });
+
+        let result = Downloader::new(settings(&url)).fetch_text(&url);
+
+        assert!(matches!(result, Err(DownloadError::Status(_, 404))));
+        assert_eq!(calls.load(Ordering::SeqCst), 1);
+    }
+
+    #[test]
+    fn should_give_up_after_configured_retries() {
+        let url = serve(|request| {
+            request
+                .respond(Response::from_string("down").with_status_code(500))
+                .unwrap()
+        });
+        let settings = DownloadSettings {
+            retries: 1,
+            ..settings(&url)
+        };
+
+        let result = Downloader::new(settings).fetch_text(&url);
+
+        assert!(matches!(result, Err(DownloadError::Status(_, 500))));
+    }
+
+    fn range_offset(request: &Request) -> Option<usize> {
+        request
+            .headers()
+            .iter()
+            .find(|h| h.field.equiv("Range"))
+            .and_then(|h| h.value.as_str().strip_prefix("bytes="))
+            .and_then(|range| range.trim_end_matches('-').parse().ok())
+    }
+
+    #[test]
+    fn should_resume_partial_download_with_range_request() {
+        const CONTENT: &str = "0123456789abcdefghij";
+        let ranges = Arc::new(AtomicUsize::new(0));
+        let received = ranges.clone();
+        let url = serve(move |request| {
+            let response = match range_offset(&request) {
+                Some(offset) => {
+                    received.store(offset, Ordering::SeqCst);
+                    let content_range = format!("bytes {}-19/20", offset);
+                    Response::from_string(&CONTENT[offset..])
+                        .with_status_code(206)
+                        .with_header(Header::from_bytes("Content-Range", content_range).unwrap())
+                }
+                None => Response::from_string(CONTENT),
+            };
+            request.respond(response).unwrap()
+        });
+        let dir = TempDir::new().unwrap();
+        let destination = dir.path().join("java-17.bin");
+        fs::write(partial_path(&destination), &CONTENT[..8]).unwrap();
+        let mut reported = Vec::new();
+
+        let download = Downloader::new(settings(&url))
+            .download(&url, &destination, &mut |done, total| {
+                reported.push((done, total))
+            })
+            .unwrap();
+
+        assert_eq!(ranges.load(Ordering::SeqCst), 8);
+        assert_eq!(download.size, 20);
+        assert_eq!(reported.first(), Some(&(8, Some(20))));
+        assert_eq!(fs::read_to_string(&destination).unwrap(), CONTENT);
+        assert!(!partial_path(&destination).exists());
+    }
+
+    #[test]
+    fn should_restart_when_server_ignores_range_request() {
+        let url = serve(|request| {
+            request
+                .respond(Response::from_string("fresh-content"))
+                .unwrap()
+        });
+        let dir = TempDir::new().unwrap();
+        let destination = dir.path().join("java-17.bin");
+        fs::write(partial_path(&destination), "stale").unwrap();
+
+        Downloader::new(settings(&url))
+            .download(&url, &destination, &mut |_, _| {})
+            .unwrap();
+
+        assert_eq!(fs::read_to_string(&destination).unwrap(), "fresh-content");
+    }
+
+    #[test]
+    fn should_restart_when_range_is_not_satisfiable() {
+        let url = serve(|request| {
+            let response = match range_offset(&request) {
+                Some(_) => Response::from_string("").with_status_code(416),
+                None => Response::from_string("fresh-content"),
+            };
+            request.respond(response).unwrap()
+        });
+        let dir = TempDir::new().unwrap();
+        let destination = dir.path().join("java-17.bin");
+        fs::write(partial_path(&destination), "too-long-partial-content").unwrap();
+
+        Downloader::new(settings(&url))
+            .download(&url, &destination, &mut |_, _| {})
+            .unwrap();
+
+        assert_eq!(fs::read_to_string(&destination).unwrap(), "fresh-content");
+    }
+
+    #[test]
+    fn should_keep_partial_file_when_connection_drops() {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let url = format!("http://{}", listener.local_addr().unwrap());
+        thread::spawn(move || {
+            for stream in listener.incoming() {
+                let mut stream = stream.unwrap();
+                let mut request = [0; 1024];
+                let _ = stream.read(&mut request);
+                let _ =
+                    stream.write_all(b"HTTP/1.1 200 OK\r\nContent-Length: 20\r\n\r\n0123456789");
+            }
+        });
+        let dir = TempDir::new().unwrap();
+        let destination = dir.path().join("java-17.bin");
+        let settings = DownloadSettings {
+            retries: 0,
+            ..settings(&url)
+        };
+
+        let result = Downloader::new(settings).download(&url, &destination, &mut |_, _| {});
+
+        assert!(result.is_err());
+        assert!(!destination.exists());
+        assert_eq!(
+            fs::read_to_string(partial_path(&destination)).unwrap(),
+            "0123456789"
+        );
+    }
+
+    #[test]
+    fn should_list_partial_downloads() {
+        let dir = TempDir::new().unwrap();
+        fs::write(dir.path().join("java-17.bin.part"), "partial").unwrap();
+        fs::write(dir.path().join("java-11.zip"), "archive").unwrap();
+
+        assert_eq!(
+            partial_downloads(dir.path()),
+            vec![dir.path().join("java-17.bin.part")]
+        );
+    }
+
+    #[test]
+    #[serial]
+    fn should_load_settings_from_config_file() {
+        let dir = TempDir::new().unwrap();
+        fs::create_dir_all(dir.path().join("etc")).unwrap();
+        fs::write(
+            dir.path().join("etc/config"),
+            "sdkman_curl_connect_timeout=3\nsdkman_curl_max_time=20\nsdkman_insecure_ssl=true\n\
+             sdkman_checksum_enable=false\n",
+        )
+        .unwrap();
+        std::env::set_var(CANDIDATES_API_ENV_VAR, "http://localhost:8080/2");
+
+        let settings = DownloadSettings::load(dir.path());
+        std::env::remove_var(CANDIDATES_API_ENV_VAR);
+
+        assert_eq!(settings.broker_url, "http://localhost:8080/2");
+        assert_eq!(settings.connect_timeout, Duration::from_secs(3));
+        assert_eq!(settings.max_time, Duration::from_secs(20));
+        assert!(settings.insecure_ssl);
+        assert!(!settings.checksum_enable);
+    }
+
+    #[test]
+    #[serial]
+    fn should_fall_back_to_default_settings() {
+        let dir = TempDir::new().unwrap();
+        std::env::remove_var(CANDIDATES_API_ENV_VAR);
+
+        assert_eq!(
+            DownloadSettings::load(dir.path()),
+            DownloadSettings::default()
+        );
+    }
+
+    #[test]
+    fn should_resolve_relative_redirect_locations() {
+        let current = "https://api.sdkman.io/2/broker/download/java";
+        assert_eq!(
+            resolve_location(current, "https://cdn.example.com/a.zip"),
+            "https://cdn.example.com/a.zip"
+        );
+        assert_eq!(
+            resolve_location(current, "/files/a.zip"),
+            "https://api.sdkman.io/files/a.zip"
+        );
+        assert_eq!(
+            resolve_location(current, "a.zip"),
+            "https://api.sdkman.io/2/broker/download/a.zip"
+        );
+    }
+}
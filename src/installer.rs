@@ -0,0 +1,143 @@
+use std::fmt;
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+
+use crate::archive::{extract_archive, ArchiveError, ArchiveFormat};
+use crate::checksum::{self, Algorithm, Checksum, ChecksumError};
+use crate::constants::{CANDIDATES_DIR, TMP_DIR};
+use crate::download::{Download, DownloadError, Downloader};
+use crate::helpers::infer_platform;
+
+const ARCHIVE_TYPE_HEADER: &str = "x-sdkman-archivetype";
+
+#[derive(Debug)]
+pub enum InstallError {
+    Download(DownloadError),
+    Checksum(ChecksumError, PathBuf),
+    Archive(ArchiveError),
+    Io(io::Error),
+}
+
+impl fmt::Display for InstallError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            InstallError::Download(e) => write!(f, "download failed: {}", e),
+            InstallError::Checksum(e, quarantined) => {
+                write!(f, "{}, archive quarantined in {}", e, quarantined.display())
+            }
+            InstallError::Archive(e) => write!(f, "{}", e),
+            InstallError::Io(e) => write!(f, "{}", e),
+        }
+    }
+}
+
+impl std::error::Error for InstallError {}
+
+impl From<DownloadError> for InstallError {
+    fn from(e: DownloadError) -> Self {
+        InstallError::Download(e)
+    }
+}
+
+impl From<ArchiveError> for InstallError {
+    fn from(e: ArchiveError) -> Self {
+        InstallError::Archive(e)
+    }
+}
+
+impl From<io::Error> for InstallError {
+    fn from(e: io::Error) -> Self {
+        InstallError::Io(e)
+    }
+}
+
+impl InstallError {
+    /// A checksum mismatch gets its own exit code so that callers can tell a tampered or
+    /// corrupted archive apart from an ordinary failure. So does an archive without any checksum.
+    pub fn exit_code(&self) -> i32 {
+        match self {
+            InstallError::Checksum(ChecksumError::Mismatch { .. } | ChecksumError::Missing, _) => {
+                exitcode::DATAERR
+            }
+            _ => 1,
+        }
+    }
+}
+
+/// Downloads, verifies and extracts a candidate version from the broker into
+/// `candidates/<candidate>/<version>`.
+pub fn install_remote(
+    sdkman_dir: &Path,
+    candidate: &str,
+    version: &str,
+    downloader: &Downloader,
+    progress: &mut dyn FnMut(u64, Option<u64>),
+) -> Result<PathBuf, InstallError> {
+    let tmp_dir = sdkman_dir.join(TMP_DIR);
+    let version_path = sdkman_dir
+        .join(CANDIDATES_DIR)
+        .join(candidate)
+        .join(version);
+    fs::create_dir_all(&tmp_dir)?;
+
+    let platform = infer_platform(sdkman_dir.to_path_buf());
+    let url = downloader.archive_url(candidate, version, &platform);
+    let download_path = tmp_dir.join(format!("{}-{}.bin", candidate, version));
+    let download = downloader.download(&url, &download_path, progress)?;
+
+    let archive_path = tmp_dir.join(format!(
+        "{}-{}.{}",
+        candidate,
+        version,
+        archive_format(&download).extension()
+    ));
+    fs::rename(&download.path, &archive_path)?;
+
+    if downloader.settings().checksum_enable {
+        let checksums = expected_checksums(downloader, &download);
+        if let Err(e) = checksum::verify(&archive_path, &checksums) {
+            let quarantined = checksum::quarantine(&archive_path, &tmp_dir)?;
+            return Err(InstallError::Checksum(e, quarantined));
+        }
+    }
+
+    let extracted = extract_archive(&archive_path, &tmp_dir, &version_path);
+    fs::remove_file(&archive_path)?;
+    extracted?;
+
+    Ok(version_path)
+}
+
+fn archive_format(download: &Download) -> ArchiveFormat {
+    match download.header(ARCHIVE_TYPE_HEADER).map(str::trim) {
+        Some("zip") => ArchiveFormat::Zip,
+        Some("tar") | Some("tar.gz") | Some("tgz") => ArchiveFormat::TarGz,
+        Some("tar.xz") | Some("txz") => ArchiveFormat::TarXz,
+        _ => {
+            let file_name = download.url.split(['?', '#']).next().unwrap_or_default();
+            ArchiveFormat::from_path(Path::new(file_name)).unwrap_or(ArchiveFormat::Zip)
+        }
+    }
+}
+
+// Checksums in the broker headers take precedence; otherwise the strongest sidecar file
+// published next to the archive is used.
+fn expected_checksums(downloader: &Downloader, download: &Download) -> Vec<Checksum> {
+    let from_headers = checksum::checksums_from_headers(&download.headers);
+    if !from_headers.is_empty() {
+        return from_headers;
+    }
+
+    Algorithm::ALL
+        .iter()
+        .find_map(|algorithm| {
+            let sidecar_url = format!("{}.{}", download.url, algorithm.extension());
+            downloader
+                .fetch_text(&sidecar_url)
+                .ok()
+                .and_then(|content| checksum::parse_sidecar(*algorithm, &content))
+        })
+        .into_iter()
+        .collect()
+}
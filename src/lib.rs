@@ -1,9 +1,34 @@
+pub mod activation;
+pub mod archive;
+pub mod checksum;
+pub mod config;
+pub mod download;
+pub mod installer;
+pub mod sdkmanrc;
+pub mod shell;
+pub mod shim;
+pub mod version;
+
 pub mod constants {
+    pub const BIN_DIR: &str = "bin";
+    pub const CANDIDATES_API_ENV_VAR: &str = "SDKMAN_CANDIDATES_API";
     pub const CANDIDATES_DIR: &str = "candidates";
     pub const CANDIDATES_FILE: &str = "candidates";
+    pub const CONFIG_FILE: &str = "config";
     pub const CURRENT_DIR: &str = "current";
+    pub const DEFAULT_CANDIDATES_API: &str = "https://api.sdkman.io/2";
     pub const DEFAULT_SDKMAN_HOME: &str = ".sdkman";
+    pub const ETC_DIR: &str = "etc";
+    pub const GIT_DIR: &str = ".git";
+    pub const INIT_SCRIPT: &str = "sdkman-init.sh";
+    pub const METADATA_DIR: &str = "metadata";
+    pub const PARTIAL_DOWNLOAD_EXTENSION: &str = "part";
+    pub const PLATFORM_FILE: &str = "platform";
+    pub const QUARANTINE_DIR: &str = "quarantine";
+    pub const SDKMANRC_FILE: &str = ".sdkmanrc";
     pub const SDKMAN_DIR_ENV_VAR: &str = "SDKMAN_DIR";
+    pub const SDKMAN_ENV_ENV_VAR: &str = "SDKMAN_ENV";
+    pub const SHIMS_DIR: &str = "shims";
     pub const TMP_DIR: &str = "tmp";
     pub const VAR_DIR: &str = "var";
 }
@@ -11,12 +36,14 @@ pub mod constants {
 pub mod helpers {
     use colored::Colorize;
     use directories::UserDirs;
-    use std::path::PathBuf;
+    use std::path::{Path, PathBuf};
     use std::{env, fs, process};
 
     use crate::constants::{
-        CANDIDATES_DIR, CANDIDATES_FILE, DEFAULT_SDKMAN_HOME, SDKMAN_DIR_ENV_VAR, VAR_DIR,
+        BIN_DIR, CANDIDATES_DIR, CANDIDATES_FILE, CURRENT_DIR, DEFAULT_SDKMAN_HOME, PLATFORM_FILE,
+        SDKMAN_DIR_ENV_VAR, VAR_DIR,
     };
+    use crate::version::Version;
 
     pub fn infer_sdkman_dir() -> PathBuf {
         match env::var(SDKMAN_DIR_ENV_VAR) {
@@ -31,6 +58,24 @@ pub mod helpers {
             .unwrap()
     }
 
+    pub fn infer_platform(sdkman_dir: PathBuf) -> String {
+        read_file_content(sdkman_dir.join(VAR_DIR).join(PLATFORM_FILE))
+            .unwrap_or_else(|| {
+                let os = match env::consts::OS {
+                    "macos" => "darwin",
+                    os => os,
+                };
+                let arch = match env::consts::ARCH {
+                    "x86_64" => "x64",
+                    "aarch64" => "arm64",
+                    "x86" => "x32",
+                    arch => arch,
+                };
+                format!("{}{}", os, arch)
+            })
+            .to_lowercase()
+    }
+
     pub fn check_file_exists(path: PathBuf) -> PathBuf {
         if path.exists() && path.is_file() {
             path
@@ -40,15 +85,13 @@ pub mod helpers {
     }
 
     pub fn read_file_content(path: PathBuf) -> Option<String> {
-        match fs::read_to_string(path) {
-            Ok(s) => Some(s),
-            Err(_) => None,
-        }
-        .filter(|s| !s.trim().is_empty())
-        .map(|s| s.trim().to_string())
+        fs::read_to_string(path)
+            .ok()
+            .filter(|s| !s.trim().is_empty())
+            .map(|s| s.trim().to_string())
     }
 
-    pub fn known_candidates<'a>(sdkman_dir: PathBuf) -> Vec<&'static str> {
+    pub fn known_candidates(sdkman_dir: PathBuf) -> Vec<&'static str> {
         let absolute_path = sdkman_dir.join(VAR_DIR).join(CANDIDATES_FILE);
         let verified_path = check_file_exists(absolute_path);
         let panic = format!(
@@ -87,11 +130,113 @@ pub mod helpers {
             process::exit(1)
         }
     }
+
+    /// Lists the versions installed for a candidate, lowest first, leaving out the current link.
+    pub fn installed_versions(base_dir: PathBuf, candidate: &str) -> Vec<String> {
+        let candidate_dir = base_dir.join(CANDIDATES_DIR).join(candidate);
+        let mut versions = fs::read_dir(candidate_dir)
+            .map(|entries| {
+                entries
+                    .filter_map(|entry| entry.ok())
+                    .filter(|entry| entry.path().is_dir() && entry.file_name() != CURRENT_DIR)
+                    .filter_map(|entry| entry.file_name().into_string().ok())
+                    .collect::<Vec<_>>()
+            })
+            .unwrap_or_default();
+        versions.sort_by_cached_key(|version| Version::parse(version));
+        versions
+    }
+
+    /// Lays out rows in left aligned columns under a header, one line per row.
+    pub fn format_table(header: &[&str], rows: &[Vec<String>]) -> String {
+        let header = header.iter().map(|h| h.to_string()).collect::<Vec<_>>();
+        let widths = (0..header.len())
+            .map(|i| {
+                rows.iter()
+                    .chain([&header])
+                    .map(|r| r[i].len())
+                    .max()
+                    .unwrap_or(0)
+            })
+            .collect::<Vec<_>>();
+        let mut table = String::new();
+        for row in [&header].into_iter().chain(rows) {
+            let line = row
+                .iter()
+                .zip(&widths)
+                .map(|(cell, width)| format!("{:<width$}", cell, width = width))
+                .collect::<Vec<_>>()
+                .join("  ");
+            table.push_str(line.trim_end());
+            table.push('\n');
+        }
+        table
+    }
+
+    pub fn get_current_version(base_dir: PathBuf, candidate: &str) -> Option<String> {
+        // First check if the candidate is installed
+        let candidate_dir = base_dir.join(CANDIDATES_DIR).join(candidate);
+        if !candidate_dir.exists() || !candidate_dir.is_dir() {
+            return None;
+        }
+
+        // Check for current symlink
+        let current_link = candidate_dir.join(CURRENT_DIR);
+        if !current_link.exists() {
+            return None;
+        }
+
+        // Get the symlink target (which should be the version)
+        if let Ok(target) = fs::read_link(&current_link) {
+            // Extract the version from the path
+            return target
+                .file_name()
+                .and_then(|name| name.to_str())
+                .map(|s| s.to_string());
+        }
+
+        // If this is not a symlink but a copied directory (fallback case), it is the installed
+        // version with the same layout, as long as only one version matches.
+        if current_link.is_dir() {
+            let layout = layout(&current_link);
+            let mut matching = installed_versions(base_dir, candidate)
+                .into_iter()
+                .filter(|version| layout == self::layout(&candidate_dir.join(version)));
+            return match (matching.next(), matching.next()) {
+                (Some(version), None) => Some(version),
+                _ => None,
+            };
+        }
+
+        None
+    }
+
+    // The names and sizes of the top level entries and of the executables of a version.
+    fn layout(version_dir: &Path) -> Vec<(PathBuf, u64)> {
+        let mut entries = [version_dir.to_path_buf(), version_dir.join(BIN_DIR)]
+            .iter()
+            .filter_map(|dir| fs::read_dir(dir).ok())
+            .flat_map(|entries| entries.filter_map(|entry| entry.ok()))
+            .filter_map(|entry| {
+                let metadata = entry.metadata().ok()?;
+                let size = if metadata.is_file() {
+                    metadata.len()
+                } else {
+                    0
+                };
+                let path = entry.path();
+                Some((path.strip_prefix(version_dir).ok()?.to_path_buf(), size))
+            })
+            .collect::<Vec<_>>();
+        entries.sort();
+        entries
+    }
 }
 
 #[cfg(test)]
 mod tests {
     use std::env;
+    use std::fs;
     use std::io::Write;
     use std::path::PathBuf;
 
@@ -99,6 +244,7 @@ mod tests {
     use tempfile::NamedTempFile;
 
     use crate::constants::SDKMAN_DIR_ENV_VAR;
+    use crate::helpers::get_current_version;
     use crate::helpers::infer_sdkman_dir;
     use crate::helpers::read_file_content;
 
@@ -106,7 +252,7 @@ mod tests {
     #[serial]
     fn should_infer_sdkman_dir_from_env_var() {
         let sdkman_dir = PathBuf::from("/home/someone/.sdkman");
-        env::set_var(SDKMAN_DIR_ENV_VAR, sdkman_dir.to_owned());
+        env::set_var(SDKMAN_DIR_ENV_VAR, &sdkman_dir);
         assert_eq!(sdkman_dir, infer_sdkman_dir());
     }
 
@@ -123,7 +269,7 @@ mod tests {
     fn should_read_content_from_file() {
         let expected_version = "5.0.0";
         let mut file = NamedTempFile::new().unwrap();
-        file.write(expected_version.as_bytes()).unwrap();
+        file.write_all(expected_version.as_bytes()).unwrap();
         let path = file.path().to_path_buf();
         let maybe_version = read_file_content(path);
         assert_eq!(maybe_version, Some(expected_version.to_string()));
@@ -137,4 +283,24 @@ mod tests {
         let maybe_version = read_file_content(path);
         assert_eq!(maybe_version, None);
     }
+
+    #[test]
+    fn should_identify_version_of_copied_current_directory() {
+        let dir = tempfile::TempDir::new().unwrap();
+        let java = dir.path().join("candidates/java");
+        for (version, launcher) in [("11.0.15-tem", "java 11"), ("17.0.3-tem", "java 17!")] {
+            fs::create_dir_all(java.join(version).join("bin")).unwrap();
+            fs::write(java.join(version).join("bin/java"), launcher).unwrap();
+        }
+        fs::create_dir_all(java.join("current/bin")).unwrap();
+        fs::write(java.join("current/bin/java"), "java 17!").unwrap();
+
+        assert_eq!(
+            get_current_version(dir.path().to_path_buf(), "java"),
+            Some("17.0.3-tem".to_string())
+        );
+
+        fs::write(java.join("current/bin/java"), "java 8").unwrap();
+        assert_eq!(get_current_version(dir.path().to_path_buf(), "java"), None);
+    }
 }
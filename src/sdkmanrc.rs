@@ -0,0 +1,412 @@
+use std::fmt;
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+
+use crate::constants::{GIT_DIR, SDKMANRC_FILE};
+
+/// The comment header written at the top of every generated `.sdkmanrc`.
+pub const HEADER: &str = "\
+# Enable auto-env through the sdkman_auto_env config
+# Add key=value pairs of SDKs to use below
+";
+
+/// Marks an `.sdkmanrc` as the top of a hierarchy, so that parent directories are not consulted.
+const ROOT_KEY: &str = "root";
+
+/// A `candidate=version` pair of an `.sdkmanrc` file, along with the file and line it was
+/// declared on.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Entry {
+    pub candidate: String,
+    pub version: String,
+    pub path: PathBuf,
+    pub line: usize,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Sdkmanrc {
+    pub path: PathBuf,
+    pub root: bool,
+    pub entries: Vec<Entry>,
+}
+
+#[derive(Debug)]
+pub enum SdkmanrcError {
+    Io(PathBuf, io::Error),
+    Malformed {
+        path: PathBuf,
+        line: usize,
+        content: String,
+    },
+    MissingVersion {
+        path: PathBuf,
+        line: usize,
+        candidate: String,
+    },
+    UnknownCandidate {
+        path: PathBuf,
+        line: usize,
+        candidate: String,
+    },
+    Duplicate {
+        path: PathBuf,
+        line: usize,
+        candidate: String,
+        first: usize,
+    },
+}
+
+impl fmt::Display for SdkmanrcError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            SdkmanrcError::Io(path, e) => write!(f, "cannot read {}: {}", path.display(), e),
+            SdkmanrcError::Malformed {
+                path,
+                line,
+                content,
+            } => write!(
+                f,
+                "{}:{}: expected candidate=version but found '{}'",
+                path.display(),
+                line,
+                content
+            ),
+            SdkmanrcError::MissingVersion {
+                path,
+                line,
+                candidate,
+            } => write!(
+                f,
+                "{}:{}: no version given for {}",
+                path.display(),
+                line,
+                candidate
+            ),
+            SdkmanrcError::UnknownCandidate {
+                path,
+                line,
+                candidate,
+            } => write!(
+                f,
+                "{}:{}: {} is not a valid candidate",
+                path.display(),
+                line,
+                candidate
+            ),
+            SdkmanrcError::Duplicate {
+                path,
+                line,
+                candidate,
+                first,
+            } => write!(
+                f,
+                "{}:{}: {} is already declared on line {}",
+                path.display(),
+                line,
+                candidate,
+                first
+            ),
+        }
+    }
+}
+
+impl std::error::Error for SdkmanrcError {}
+
+impl Sdkmanrc {
+    /// Parses the content of an `.sdkmanrc` file. Comments, blank lines, surrounding whitespace
+    /// and CRLF line endings are ignored; entries keep the order in which they are declared.
+    pub fn parse(
+        path: &Path,
+        content: &str,
+        known_candidates: &[&str],
+    ) -> Result<Sdkmanrc, SdkmanrcError> {
+        let mut entries: Vec<Entry> = Vec::new();
+        let mut root = false;
+        for (index, raw) in content.lines().enumerate() {
+            let line = index + 1;
+            let trimmed = strip_comment(raw).trim();
+            if trimmed.is_empty() {
+                continue;
+            }
+            let Some((candidate, version)) = trimmed.split_once('=') else {
+                return Err(SdkmanrcError::Malformed {
+                    path: path.to_path_buf(),
+                    line,
+                    content: trimmed.to_string(),
+                });
+            };
+            let candidate = candidate.trim().to_string();
+            let version = version.trim().to_string();
+            if candidate.is_empty() || candidate.contains(char::is_whitespace) {
+                return Err(SdkmanrcError::Malformed {
+                    path: path.to_path_buf(),
+                    line,
+                    content: trimmed.to_string(),
+                });
+            }
+            if candidate == ROOT_KEY {
+                root = match version.as_str() {
+                    "true" => true,
+                    "false" => false,
+                    _ => {
+                        return Err(SdkmanrcError::Malformed {
+                            path: path.to_path_buf(),
+                            line,
+                            content: trimmed.to_string(),
+                        })
+                    }
+                };
+                continue;
+            }
+            if !known_candidates.contains(&candidate.as_str()) {
+                return Err(SdkmanrcError::UnknownCandidate {
+                    path: path.to_path_buf(),
+                    line,
+                    candidate,
+                });
+            }
+            if version.is_empty() {
+                return Err(SdkmanrcError::MissingVersion {
+                    path: path.to_path_buf(),
+                    line,
+                    candidate,
+                });
+            }
+            if let Some(first) = entries.iter().find(|e| e.candidate == candidate) {
+                return Err(SdkmanrcError::Duplicate {
+                    path: path.to_path_buf(),
+                    line,
+                    candidate,
+                    first: first.line,
+                });
+            }
+            entries.push(Entry {
+                candidate,
+                version,
+                path: path.to_path_buf(),
+                line,
+            });
+        }
+        Ok(Sdkmanrc {
+            path: path.to_path_buf(),
+            root,
+            entries,
+        })
+    }
+
+    pub fn read(path: &Path, known_candidates: &[&str]) -> Result<Sdkmanrc, SdkmanrcError> {
+        let content =
+            fs::read_to_string(path).map_err(|e| SdkmanrcError::Io(path.to_path_buf(), e))?;
+        Sdkmanrc::parse(path, &content, known_candidates)
+    }
+
+    /// Reads every `.sdkmanrc` from `start` up through its parents, nearest first. The walk stops
+    /// after a file marked `root=true`, at the root of a git repository, or at the filesystem
+    /// root.
+    pub fn read_hierarchy(
+        start: &Path,
+        known_candidates: &[&str],
+    ) -> Result<Vec<Sdkmanrc>, SdkmanrcError> {
+        let mut files = Vec::new();
+        for dir in start.ancestors() {
+            let path = dir.join(SDKMANRC_FILE);
+            if path.is_file() {
+                let sdkmanrc = Sdkmanrc::read(&path, known_candidates)?;
+                let root = sdkmanrc.root;
+                files.push(sdkmanrc);
+                if root {
+                    break;
+                }
+            }
+            if dir.join(GIT_DIR).exists() {
+                break;
+            }
+        }
+        Ok(files)
+    }
+
+    /// Finds the nearest `.sdkmanrc` of `start` without reading it, within the same boundaries
+    /// as [`Sdkmanrc::read_hierarchy`].
+    pub fn nearest(start: &Path) -> Option<PathBuf> {
+        for dir in start.ancestors() {
+            let path = dir.join(SDKMANRC_FILE);
+            if path.is_file() {
+                return Some(path);
+            }
+            if dir.join(GIT_DIR).exists() {
+                return None;
+            }
+        }
+        None
+    }
+}
+
+/// Merges a hierarchy of files as returned by [`Sdkmanrc::read_hierarchy`]. Entries of nearer
+/// files replace those of their parents, and each entry keeps the file it came from.
+pub fn merge(files: &[Sdkmanrc]) -> Vec<Entry> {
+    let mut merged: Vec<Entry> = Vec::new();
+    for entry in files.iter().rev().flat_map(|file| &file.entries) {
+        match merged.iter_mut().find(|e| e.candidate == entry.candidate) {
+            Some(existing) => *existing = entry.clone(),
+            None => merged.push(entry.clone()),
+        }
+    }
+    merged
+}
+
+// Versions never contain a '#', so anything after one is a trailing comment.
+fn strip_comment(line: &str) -> &str {
+    line.split_once('#').map_or(line, |(before, _)| before)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::fs;
+    use std::path::Path;
+
+    use tempfile::TempDir;
+
+    use crate::sdkmanrc::{merge, Entry, Sdkmanrc, SdkmanrcError};
+
+    const KNOWN: [&str; 3] = ["java", "maven", "gradle"];
+
+    fn entry(candidate: &str, version: &str, line: usize) -> Entry {
+        Entry {
+            candidate: candidate.to_string(),
+            version: version.to_string(),
+            path: Path::new(".sdkmanrc").to_path_buf(),
+            line,
+        }
+    }
+
+    #[test]
+    fn should_parse_entries_in_order() {
+        let content = "# Enable auto-env through the sdkman_auto_env config\r\n\
+                       \r\n  java = 17.0.3-tem  \r\nmaven=3.9.6 # build tool\r\n\tgradle=8.5\n";
+        let rc = Sdkmanrc::parse(Path::new(".sdkmanrc"), content, &KNOWN).unwrap();
+
+        assert_eq!(
+            rc.entries,
+            vec![
+                entry("java", "17.0.3-tem", 3),
+                entry("maven", "3.9.6", 4),
+                entry("gradle", "8.5", 5),
+            ]
+        );
+    }
+
+    #[test]
+    fn should_report_unknown_candidate_with_file_and_line() {
+        let content = "java=17.0.3-tem\njvaa=11.0.2-tem\n";
+        let error = Sdkmanrc::parse(Path::new("/work/.sdkmanrc"), content, &KNOWN).unwrap_err();
+
+        assert!(matches!(
+            error,
+            SdkmanrcError::UnknownCandidate { line: 2, .. }
+        ));
+        assert_eq!(
+            error.to_string(),
+            "/work/.sdkmanrc:2: jvaa is not a valid candidate"
+        );
+    }
+
+    #[test]
+    fn should_reject_malformed_lines() {
+        let path = Path::new(".sdkmanrc");
+        let cases = [
+            (
+                "java 17\n",
+                "expected candidate=version but found 'java 17'",
+            ),
+            ("java=\n", "no version given for java"),
+            ("=17\n", "expected candidate=version but found '=17'"),
+            (
+                "java=17\nmaven=3\njava=21\n",
+                "java is already declared on line 1",
+            ),
+        ];
+        for (content, message) in cases {
+            let error = Sdkmanrc::parse(path, content, &KNOWN).unwrap_err();
+            assert!(error.to_string().ends_with(message), "{}", error);
+        }
+    }
+
+    #[test]
+    fn should_read_file_from_disk() {
+        let dir = TempDir::new().unwrap();
+        let path = dir.path().join(".sdkmanrc");
+        fs::write(&path, "java=21.0.2-tem\n").unwrap();
+
+        let rc = Sdkmanrc::read(&path, &KNOWN).unwrap();
+        assert_eq!(rc.path, path);
+        assert_eq!(rc.entries[0].path, path);
+        assert_eq!(rc.entries[0].version, "21.0.2-tem");
+
+        let missing = Sdkmanrc::read(&dir.path().join("missing"), &KNOWN).unwrap_err();
+        assert!(matches!(missing, SdkmanrcError::Io(..)));
+    }
+
+    #[test]
+    fn should_merge_hierarchy_with_nearer_files_winning() {
+        let dir = TempDir::new().unwrap();
+        let module = dir.path().join("repo/services/api");
+        fs::create_dir_all(&module).unwrap();
+        fs::create_dir_all(dir.path().join("repo/.git")).unwrap();
+        fs::write(dir.path().join(".sdkmanrc"), "gradle=8.5\n").unwrap();
+        fs::write(
+            dir.path().join("repo/.sdkmanrc"),
+            "java=17.0.3-tem\nmaven=3.9.6\n",
+        )
+        .unwrap();
+        fs::write(module.join(".sdkmanrc"), "maven=3.8.8\n").unwrap();
+
+        let files = Sdkmanrc::read_hierarchy(&module, &KNOWN).unwrap();
+        assert_eq!(files.len(), 2);
+
+        let merged = merge(&files);
+        let summary = merged
+            .iter()
+            .map(|e| (e.candidate.as_str(), e.version.as_str(), e.path.clone()))
+            .collect::<Vec<_>>();
+        assert_eq!(
+            summary,
+            vec![
+                ("java", "17.0.3-tem", dir.path().join("repo/.sdkmanrc")),
+                ("maven", "3.8.8", module.join(".sdkmanrc")),
+            ]
+        );
+    }
+
+    #[test]
+    fn should_stop_at_file_marked_as_root() {
+        let dir = TempDir::new().unwrap();
+        let module = dir.path().join("module");
+        fs::create_dir_all(&module).unwrap();
+        fs::write(dir.path().join(".sdkmanrc"), "java=17.0.3-tem\n").unwrap();
+        fs::write(module.join(".sdkmanrc"), "root = true\nmaven=3.9.6\n").unwrap();
+
+        let files = Sdkmanrc::read_hierarchy(&module, &KNOWN).unwrap();
+
+        assert_eq!(files.len(), 1);
+        assert!(files[0].root);
+        assert_eq!(merge(&files)[0].candidate, "maven");
+    }
+
+    #[test]
+    fn should_find_nearest_file_within_the_repository() {
+        let dir = TempDir::new().unwrap();
+        let module = dir.path().join("repo/services/api");
+        fs::create_dir_all(&module).unwrap();
+        fs::write(dir.path().join(".sdkmanrc"), "gradle=8.5\n").unwrap();
+        fs::write(dir.path().join("repo/services/.sdkmanrc"), "maven=3.9.6\n").unwrap();
+
+        assert_eq!(
+            Sdkmanrc::nearest(&module),
+            Some(dir.path().join("repo/services/.sdkmanrc"))
+        );
+
+        fs::create_dir_all(module.join(".git")).unwrap();
+        assert_eq!(Sdkmanrc::nearest(&module), None);
+    }
+}
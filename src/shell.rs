@@ -0,0 +1,459 @@
+use std::env;
+use std::path::Path;
+
+use clap::ValueEnum;
+
+const PATH_VARIABLE: &str = "PATH";
+
+// The `sdk` function runs the native subcommands from libexec, evaluating the output of those
+// that change the environment of the calling shell, but not the reports of `env check` and
+// `env sources`. Subcommands without a native binary are handed to the bash implementation. Bash
+// and zsh load it on first use, keeping its `sdk` function under another name so that its state,
+// such as offline mode, stays in the shell. The other shells run it in a bash process.
+const POSIX_SDK_FUNCTION: &str = r#"sdk() {
+    local command="${1:-help}"
+    [ $# -gt 0 ] && shift
+    case "$command" in
+        c) command=current ;;
+        d) command=default ;;
+        e) command=env ;;
+        h) command=home ;;
+        i) command=install ;;
+        ls) command=list ;;
+        rm) command=uninstall ;;
+        u) command=use ;;
+        v) command=version ;;
+    esac
+    local binary="$SDKMAN_DIR/libexec/$command"
+    if [ ! -x "$binary" ]; then
+        if ! type __sdkman_bash_sdk >/dev/null 2>&1; then
+            if [ ! -f "$SDKMAN_DIR/bin/sdkman-init.sh" ]; then
+                echo "Invalid command: $command" >&2
+                return 1
+            fi
+            local native_sdk
+            native_sdk="$(typeset -f sdk)"
+            . "$SDKMAN_DIR/bin/sdkman-init.sh"
+            eval "__sdkman_bash_$(typeset -f sdk)"
+            eval "$native_sdk"
+        fi
+        __sdkman_bash_sdk "$command" "$@"
+        return
+    fi
+    case "$command ${1:-}" in
+        "env check"|"env sources") "$binary" "$@" ;;
+        env*|use*)
+            local output sdk_status
+            output="$("$binary" --shell {shell} "$@")"
+            sdk_status=$?
+            eval "$output"
+            return $sdk_status
+            ;;
+        *) "$binary" "$@" ;;
+    esac
+}"#;
+
+const FISH_SDK_FUNCTION: &str = r#"function sdk
+    set -l command help
+    if test (count $argv) -gt 0
+        set command $argv[1]
+        set -e argv[1]
+    end
+    switch $command
+        case c; set command current
+        case d; set command default
+        case e; set command env
+        case h; set command home
+        case i; set command install
+        case ls; set command list
+        case rm; set command uninstall
+        case u; set command use
+        case v; set command version
+    end
+    set -l binary "$SDKMAN_DIR/libexec/$command"
+    if not test -x "$binary"
+        if test -f "$SDKMAN_DIR/bin/sdkman-init.sh"
+            bash -c '. "$SDKMAN_DIR/bin/sdkman-init.sh" && sdk "$@"' sdk $command $argv
+            return $status
+        end
+        echo "Invalid command: $command" >&2
+        return 1
+    end
+    if test $command = env; and contains -- "$argv[1]" check sources
+        "$binary" $argv
+        return $status
+    end
+    switch $command
+        case env use
+            set -l output ("$binary" --shell fish $argv)
+            set -l code $status
+            printf '%s\n' $output | source
+            return $code
+        case '*'
+            "$binary" $argv
+    end
+end"#;
+
+const PWSH_SDK_FUNCTION: &str = r#"function sdk {
+    $command = if ($args.Count -gt 0) { $args[0] } else { 'help' }
+    $rest = @($args | Select-Object -Skip 1)
+    $aliases = @{ c = 'current'; d = 'default'; e = 'env'; h = 'home'; i = 'install'; ls = 'list'; rm = 'uninstall'; u = 'use'; v = 'version' }
+    if ($aliases.ContainsKey($command)) { $command = $aliases[$command] }
+    $binary = Join-Path $env:SDKMAN_DIR "libexec/$command"
+    if (-not (Test-Path $binary) -and -not (Test-Path "$binary.exe")) {
+        if (Test-Path (Join-Path $env:SDKMAN_DIR 'bin/sdkman-init.sh')) {
+            & bash -c '. "$SDKMAN_DIR/bin/sdkman-init.sh" && sdk "$@"' sdk $command @rest
+            return
+        }
+        Write-Error "Invalid command: $command"
+        return
+    }
+    if ($command -eq 'use' -or ($command -eq 'env' -and $rest[0] -notin 'check', 'sources')) {
+        & $binary --shell pwsh @rest | Out-String | Invoke-Expression
+    } else {
+        & $binary @rest
+    }
+}"#;
+
+// Nushell cannot evaluate code at runtime, so the assignments are parsed and loaded instead.
+// Being wrapped, the function passes flags such as `--force` on to the binary untouched.
+const NU_SDK_FUNCTION: &str = r#"def --env --wrapped sdk [...args] {
+    let aliases = {c: current, d: default, e: env, h: home, i: install, ls: list, rm: uninstall, u: use, v: version}
+    let command = if ($args | is-empty) { "help" } else { $args.0 }
+    let command = if $command in $aliases { $aliases | get $command } else { $command }
+    let rest = ($args | skip 1)
+    let binary = ($env.SDKMAN_DIR | path join libexec $command)
+    if not ($binary | path exists) {
+        if ($env.SDKMAN_DIR | path join bin sdkman-init.sh | path exists) {
+            ^bash -c '. "$SDKMAN_DIR/bin/sdkman-init.sh" && sdk "$@"' sdk $command ...$rest
+            return
+        }
+        error make {msg: $"Invalid command: ($command)"}
+    }
+    let subcommand = ($rest | append "" | first)
+    if $command == use or ($command == env and $subcommand not-in [check sources]) {
+        for line in (^$binary --shell nu ...$rest | lines) {
+            if ($line | str starts-with "hide-env -i ") {
+                hide-env -i ($line | str replace "hide-env -i " "")
+            } else {
+                let assignment = ($line | parse "$env.{name} = {value}" | first)
+                load-env {($assignment.name): ($assignment.value | from nuon)}
+            }
+        }
+    } else {
+        ^$binary ...$rest
+    }
+}"#;
+
+// With auto-env, `sdk env hook` runs whenever the directory changes, given the directory the
+// hook last ran in. The first run starts from the root, so that a shell opened inside a project
+// picks up its `.sdkmanrc`. Bash has no directory change event and checks before every prompt.
+const BASH_AUTO_ENV_HOOK: &str = r#"__sdkman_env_hook() {
+    if [ "$PWD" != "${__sdkman_env_dir:-}" ]; then
+        sdk env hook "${__sdkman_env_dir:-/}"
+        __sdkman_env_dir="$PWD"
+    fi
+}
+case ";${PROMPT_COMMAND:-};" in
+    *";__sdkman_env_hook;"*) ;;
+    *) PROMPT_COMMAND="__sdkman_env_hook${PROMPT_COMMAND:+;$PROMPT_COMMAND}" ;;
+esac"#;
+
+const ZSH_AUTO_ENV_HOOK: &str = r#"__sdkman_env_hook() {
+    sdk env hook "${__sdkman_env_dir:-/}"
+    __sdkman_env_dir="$PWD"
+}
+(( ${chpwd_functions[(I)__sdkman_env_hook]} )) || chpwd_functions+=(__sdkman_env_hook)
+__sdkman_env_hook"#;
+
+const FISH_AUTO_ENV_HOOK: &str = r#"function __sdkman_env_hook --on-variable PWD
+    set -q __sdkman_env_dir; or set -g __sdkman_env_dir /
+    sdk env hook $__sdkman_env_dir
+    set -g __sdkman_env_dir $PWD
+end
+__sdkman_env_hook"#;
+
+const PWSH_AUTO_ENV_HOOK: &str = r#"if (-not $global:__sdkman_prompt) {
+    $global:__sdkman_prompt = $function:prompt
+    function global:prompt {
+        if ($PWD.Path -ne $global:__sdkman_env_dir) {
+            $previous = if ($global:__sdkman_env_dir) { $global:__sdkman_env_dir } else { [IO.Path]::GetPathRoot($PWD.Path) }
+            sdk env hook $previous
+            $global:__sdkman_env_dir = $PWD.Path
+        }
+        & $global:__sdkman_prompt
+    }
+}"#;
+
+// Nushell runs the hook on the first prompt too, without a previous directory.
+const NU_AUTO_ENV_HOOK: &str = r#"$env.config = ($env.config | upsert hooks.env_change.PWD {|config|
+    ($config.hooks?.env_change?.PWD? | default []) | append {|before, after| sdk env hook ($before | default "/") }
+})"#;
+
+/// The shell dialects that native subcommands can print environment changes for.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum Shell {
+    Bash,
+    Zsh,
+    Fish,
+    Pwsh,
+    Nu,
+}
+
+impl Shell {
+    /// Infers the dialect from the basename of `$SHELL`, falling back to bash, whose syntax every
+    /// POSIX shell understands.
+    pub fn detect() -> Shell {
+        env::var("SHELL")
+            .ok()
+            .and_then(|shell| Shell::from_program(&shell))
+            .unwrap_or(Shell::Bash)
+    }
+
+    pub fn from_program(program: &str) -> Option<Shell> {
+        let name = Path::new(program).file_name()?.to_str()?;
+        match name.trim_end_matches(".exe") {
+            "bash" | "sh" | "dash" | "ksh" => Some(Shell::Bash),
+            "zsh" => Some(Shell::Zsh),
+            "fish" => Some(Shell::Fish),
+            "pwsh" | "powershell" => Some(Shell::Pwsh),
+            "nu" => Some(Shell::Nu),
+            _ => None,
+        }
+    }
+
+    /// Renders an assignment of an environment variable. `PATH` becomes a list in the shells
+    /// that treat it as one.
+    pub fn export(&self, name: &str, value: &str) -> String {
+        match self {
+            Shell::Bash | Shell::Zsh => format!("export {}={}", name, posix_quote(value)),
+            Shell::Fish if name == PATH_VARIABLE => {
+                let elements = path_elements(value)
+                    .map(|e| fish_quote(&e))
+                    .collect::<Vec<_>>();
+                format!("set -gx {} {}", name, elements.join(" "))
+            }
+            Shell::Fish => format!("set -gx {} {}", name, fish_quote(value)),
+            Shell::Pwsh => format!("$env:{} = {}", name, pwsh_quote(value)),
+            Shell::Nu if name == PATH_VARIABLE => {
+                let elements = path_elements(value)
+                    .map(|e| nu_quote(&e))
+                    .collect::<Vec<_>>();
+                format!("$env.{} = [{}]", name, elements.join(", "))
+            }
+            Shell::Nu => format!("$env.{} = {}", name, nu_quote(value)),
+        }
+    }
+
+    /// Renders the insertion of `value`, one or more directories, at the front of the `PATH` that
+    /// the shell has when the code runs. Directories already on it are skipped, so that nested
+    /// shells and sourcing the code again do not add duplicates.
+    pub fn prepend_path(&self, value: &str) -> String {
+        // Each directory is put in front in turn, so the last one goes first.
+        let elements = path_elements(value).collect::<Vec<_>>();
+        let reversed = elements.iter().rev();
+        match self {
+            Shell::Bash | Shell::Zsh => format!(
+                "for __sdkman_bin in {1}; do case \":${0}:\" in *\":$__sdkman_bin:\"*) ;; \
+                 *) {0}=\"$__sdkman_bin:${0}\" ;; esac; done; unset __sdkman_bin; export {0}",
+                PATH_VARIABLE,
+                reversed.map(|e| posix_quote(e)).collect::<Vec<_>>().join(" ")
+            ),
+            Shell::Fish => format!(
+                "for __sdkman_bin in {1}; contains -- $__sdkman_bin ${0}; \
+                 or set -gx {0} $__sdkman_bin ${0}; end",
+                PATH_VARIABLE,
+                reversed.map(|e| fish_quote(e)).collect::<Vec<_>>().join(" ")
+            ),
+            Shell::Pwsh => format!(
+                "foreach ($__sdkman_bin in @({1})) {{ if (($env:{0} -split [IO.Path]::PathSeparator) \
+                 -notcontains $__sdkman_bin) {{ $env:{0} = $__sdkman_bin + [IO.Path]::PathSeparator \
+                 + $env:{0} }} }}",
+                PATH_VARIABLE,
+                reversed.map(|e| pwsh_quote(e)).collect::<Vec<_>>().join(", ")
+            ),
+            Shell::Nu => format!(
+                "$env.{0} = ([{1}] | reduce -f ($env.{0} | split row (char esep)) {{|bin, path| \
+                 if $bin in $path {{ $path }} else {{ $path | prepend $bin }} }})",
+                PATH_VARIABLE,
+                reversed.map(|e| nu_quote(e)).collect::<Vec<_>>().join(", ")
+            ),
+        }
+    }
+
+    /// Renders the definition of the `sdk` function that shell startup needs.
+    pub fn sdk_function(&self) -> String {
+        match self {
+            Shell::Bash => POSIX_SDK_FUNCTION.replace("{shell}", "bash"),
+            Shell::Zsh => POSIX_SDK_FUNCTION.replace("{shell}", "zsh"),
+            Shell::Fish => FISH_SDK_FUNCTION.to_string(),
+            Shell::Pwsh => PWSH_SDK_FUNCTION.to_string(),
+            Shell::Nu => NU_SDK_FUNCTION.to_string(),
+        }
+    }
+
+    /// Renders the hook that switches versions with `sdk env hook` when the directory changes,
+    /// which shell startup adds when `sdkman_auto_env` is enabled.
+    pub fn auto_env_hook(&self) -> &'static str {
+        match self {
+            Shell::Bash => BASH_AUTO_ENV_HOOK,
+            Shell::Zsh => ZSH_AUTO_ENV_HOOK,
+            Shell::Fish => FISH_AUTO_ENV_HOOK,
+            Shell::Pwsh => PWSH_AUTO_ENV_HOOK,
+            Shell::Nu => NU_AUTO_ENV_HOOK,
+        }
+    }
+
+    /// Renders the removal of an environment variable that may not be set.
+    pub fn unset(&self, name: &str) -> String {
+        match self {
+            Shell::Bash | Shell::Zsh => format!("unset {}", name),
+            Shell::Fish => format!("set -e {}", name),
+            Shell::Pwsh => format!("Remove-Item -ErrorAction SilentlyContinue Env:{}", name),
+            Shell::Nu => format!("hide-env -i {}", name),
+        }
+    }
+}
+
+fn path_elements(value: &str) -> impl Iterator<Item = String> + '_ {
+    env::split_paths(value)
+        .filter(|e| !e.as_os_str().is_empty())
+        .map(|e| e.to_string_lossy().to_string())
+}
+
+// Single quotes take everything literally, so a quote is closed, escaped and reopened.
+fn posix_quote(value: &str) -> String {
+    format!("'{}'", value.replace('\'', r"'\''"))
+}
+
+// Inside fish single quotes only the backslash and the quote itself need escaping.
+fn fish_quote(value: &str) -> String {
+    format!("'{}'", value.replace('\\', r"\\").replace('\'', r"\'"))
+}
+
+// PowerShell escapes a single quote inside a verbatim string by doubling it.
+fn pwsh_quote(value: &str) -> String {
+    format!("'{}'", value.replace('\'', "''"))
+}
+
+// Nushell single quotes cannot hold a quote, so double quotes with escapes are used instead.
+fn nu_quote(value: &str) -> String {
+    format!("\"{}\"", value.replace('\\', r"\\").replace('"', "\\\""))
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::shell::Shell;
+
+    #[test]
+    fn should_detect_shell_from_program_path() {
+        assert_eq!(Shell::from_program("/usr/bin/zsh"), Some(Shell::Zsh));
+        assert_eq!(
+            Shell::from_program("/opt/homebrew/bin/fish"),
+            Some(Shell::Fish)
+        );
+        assert_eq!(Shell::from_program("/usr/local/bin/nu"), Some(Shell::Nu));
+        assert_eq!(Shell::from_program("pwsh.exe"), Some(Shell::Pwsh));
+        assert_eq!(Shell::from_program("/bin/sh"), Some(Shell::Bash));
+        assert_eq!(Shell::from_program("/bin/tcsh"), None);
+    }
+
+    #[test]
+    fn should_quote_assignments_for_each_dialect() {
+        let value = r"/home/o'neil/my \sdks";
+        assert_eq!(
+            Shell::Bash.export("JAVA_HOME", value),
+            r"export JAVA_HOME='/home/o'\''neil/my \sdks'"
+        );
+        assert_eq!(
+            Shell::Fish.export("JAVA_HOME", value),
+            r"set -gx JAVA_HOME '/home/o\'neil/my \\sdks'"
+        );
+        assert_eq!(
+            Shell::Pwsh.export("JAVA_HOME", value),
+            r"$env:JAVA_HOME = '/home/o''neil/my \sdks'"
+        );
+        assert_eq!(
+            Shell::Nu.export("JAVA_HOME", r#"/home/"o'neil"/my \sdks"#),
+            r#"$env.JAVA_HOME = "/home/\"o'neil\"/my \\sdks""#
+        );
+    }
+
+    #[test]
+    fn should_export_path_as_list_where_the_shell_expects_one() {
+        let path = "/sdkman/candidates/java/17/bin:/usr/bin";
+        assert_eq!(
+            Shell::Zsh.export("PATH", path),
+            "export PATH='/sdkman/candidates/java/17/bin:/usr/bin'"
+        );
+        assert_eq!(
+            Shell::Fish.export("PATH", path),
+            "set -gx PATH '/sdkman/candidates/java/17/bin' '/usr/bin'"
+        );
+        assert_eq!(
+            Shell::Pwsh.export("PATH", path),
+            "$env:PATH = '/sdkman/candidates/java/17/bin:/usr/bin'"
+        );
+        assert_eq!(
+            Shell::Nu.export("PATH", path),
+            r#"$env.PATH = ["/sdkman/candidates/java/17/bin", "/usr/bin"]"#
+        );
+    }
+
+    #[test]
+    fn should_prepend_to_the_path_of_the_shell() {
+        let bin_dirs = "/sdkman/shims:/sdkman/candidates/java/current/bin";
+        assert_eq!(
+            Shell::Bash.prepend_path(bin_dirs),
+            "for __sdkman_bin in '/sdkman/candidates/java/current/bin' '/sdkman/shims'; do \
+             case \":$PATH:\" in *\":$__sdkman_bin:\"*) ;; *) PATH=\"$__sdkman_bin:$PATH\" ;; esac; \
+             done; unset __sdkman_bin; export PATH"
+        );
+        assert_eq!(
+            Shell::Fish.prepend_path(bin_dirs),
+            "for __sdkman_bin in '/sdkman/candidates/java/current/bin' '/sdkman/shims'; \
+             contains -- $__sdkman_bin $PATH; or set -gx PATH $__sdkman_bin $PATH; end"
+        );
+        assert!(Shell::Pwsh.prepend_path(bin_dirs).starts_with(
+            "foreach ($__sdkman_bin in @('/sdkman/candidates/java/current/bin', '/sdkman/shims'))"
+        ));
+        assert!(Shell::Nu.prepend_path(bin_dirs).starts_with(
+            r#"$env.PATH = (["/sdkman/candidates/java/current/bin", "/sdkman/shims"] | reduce"#
+        ));
+    }
+
+    #[test]
+    fn should_unset_variables_for_each_dialect() {
+        assert_eq!(Shell::Bash.unset("MAVEN_HOME"), "unset MAVEN_HOME");
+        assert_eq!(Shell::Fish.unset("MAVEN_HOME"), "set -e MAVEN_HOME");
+        assert_eq!(
+            Shell::Pwsh.unset("MAVEN_HOME"),
+            "Remove-Item -ErrorAction SilentlyContinue Env:MAVEN_HOME"
+        );
+        assert_eq!(Shell::Nu.unset("MAVEN_HOME"), "hide-env -i MAVEN_HOME");
+    }
+
+    #[test]
+    fn should_evaluate_subcommands_in_the_dialect_of_the_function() {
+        assert!(Shell::Zsh
+            .sdk_function()
+            .contains(r#""$binary" --shell zsh "$@""#));
+        assert!(Shell::Fish.sdk_function().starts_with("function sdk\n"));
+        assert!(Shell::Pwsh.sdk_function().contains("--shell pwsh @rest"));
+        assert!(Shell::Nu.sdk_function().contains("--shell nu ...$rest"));
+    }
+
+    #[test]
+    fn should_run_env_hook_from_every_auto_env_hook() {
+        for shell in [Shell::Bash, Shell::Zsh, Shell::Fish, Shell::Pwsh, Shell::Nu] {
+            assert!(shell.auto_env_hook().contains("sdk env hook "));
+        }
+    }
+
+    #[test]
+    fn should_hand_other_subcommands_to_the_bash_implementation() {
+        for shell in [Shell::Bash, Shell::Zsh, Shell::Fish, Shell::Pwsh, Shell::Nu] {
+            assert!(shell.sdk_function().contains("bin/sdkman-init.sh"));
+        }
+    }
+}
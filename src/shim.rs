@@ -0,0 +1,286 @@
+use std::env::consts::EXE_SUFFIX;
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+
+use crate::activation::{home_variable, resolve_entries};
+use crate::constants::{BIN_DIR, CANDIDATES_DIR, CURRENT_DIR, SHIMS_DIR};
+use crate::helpers::{get_current_version, installed_versions};
+use crate::sdkmanrc::{merge, Entry, Sdkmanrc, SdkmanrcError};
+
+/// Where the version that a shim runs was taken from.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Origin {
+    Override(String),
+    Home(String),
+    Sdkmanrc(PathBuf),
+    Current,
+}
+
+/// The executable that a shim hands over to.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Target {
+    pub candidate: String,
+    pub version: String,
+    pub origin: Origin,
+    pub executable: PathBuf,
+}
+
+/// The variable that pins a candidate for the session, e.g. `SDKMAN_JAVA_VERSION`. It takes
+/// priority over any `.sdkmanrc`.
+pub fn override_variable(candidate: &str) -> String {
+    format!(
+        "SDKMAN_{}_VERSION",
+        candidate.to_uppercase().replace('-', "_")
+    )
+}
+
+/// Lists the executables found in the `bin` directory of any installed version, sorted and
+/// without duplicates.
+pub fn executables(sdkman_dir: &Path, candidates: &[&str]) -> Vec<String> {
+    let mut names = candidates
+        .iter()
+        .flat_map(|candidate| {
+            installed_versions(sdkman_dir.to_path_buf(), candidate)
+                .into_iter()
+                .map(move |version| bin_dir(sdkman_dir, candidate, &version))
+        })
+        .filter_map(|dir| fs::read_dir(dir).ok())
+        .flat_map(|entries| entries.filter_map(|entry| entry.ok()))
+        .filter(|entry| is_executable(&entry.path()))
+        .filter_map(|entry| entry.file_name().into_string().ok())
+        .collect::<Vec<_>>();
+    names.sort();
+    names.dedup();
+    names
+}
+
+/// Links every executable of [`executables`] to `launcher` in `$SDKMAN_DIR/shims`, and removes
+/// the links of executables that are gone. Returns the names of the links created and removed.
+pub fn rehash(
+    sdkman_dir: &Path,
+    launcher: &Path,
+    candidates: &[&str],
+) -> io::Result<(Vec<String>, Vec<String>)> {
+    let shims_dir = sdkman_dir.join(SHIMS_DIR);
+    fs::create_dir_all(&shims_dir)?;
+    let names = executables(sdkman_dir, candidates);
+
+    let mut removed = Vec::new();
+    for entry in fs::read_dir(&shims_dir)? {
+        let entry = entry?;
+        let name = entry.file_name().to_string_lossy().to_string();
+        if entry.path().is_symlink() && !names.contains(&name) {
+            symlink::remove_symlink_file(entry.path())?;
+            removed.push(name);
+        }
+    }
+
+    let mut created = Vec::new();
+    for name in names {
+        let shim = shims_dir.join(&name);
+        if shim.is_symlink() {
+            if fs::read_link(&shim)? == launcher {
+                continue;
+            }
+            symlink::remove_symlink_file(&shim)?;
+        }
+        symlink::symlink_file(launcher, &shim)?;
+        created.push(name);
+    }
+    removed.sort();
+    Ok((created, removed))
+}
+
+/// Picks the version of the candidate providing `name` for a shim started in `dir`: the session
+/// override first, then the version that `sdk use` or `sdk env` put in `*_HOME`, then the
+/// `.sdkmanrc` hierarchy, and then the `current` link. `variable` looks up the environment.
+/// Candidates that do not have `name` in any installed version are skipped.
+pub fn resolve(
+    sdkman_dir: &Path,
+    dir: &Path,
+    name: &str,
+    variable: &dyn Fn(&str) -> Option<String>,
+    candidates: &[&str],
+) -> Result<Option<Target>, SdkmanrcError> {
+    let mut entries: Option<Vec<Entry>> = None;
+    for candidate in candidates {
+        let provides = installed_versions(sdkman_dir.to_path_buf(), candidate)
+            .iter()
+            .any(|version| is_executable(&bin_dir(sdkman_dir, candidate, version).join(name)));
+        if !provides {
+            continue;
+        }
+
+        let entries = match &entries {
+            Some(entries) => entries,
+            None => {
+                let files = Sdkmanrc::read_hierarchy(dir, candidates)?;
+                entries.insert(resolve_entries(sdkman_dir, &merge(&files)))
+            }
+        };
+        let override_name = override_variable(candidate);
+        let home_name = home_variable(candidate);
+        let active =
+            variable(&home_name).and_then(|home| active_version(sdkman_dir, candidate, &home));
+        let selected = match variable(&override_name).filter(|v| !v.is_empty()) {
+            Some(version) => Some((version, Origin::Override(override_name))),
+            None if active.is_some() => active.map(|version| (version, Origin::Home(home_name))),
+            None => match entries.iter().find(|e| e.candidate == *candidate) {
+                Some(entry) => Some((
+                    entry.version.to_owned(),
+                    Origin::Sdkmanrc(entry.path.to_owned()),
+                )),
+                None => get_current_version(sdkman_dir.to_path_buf(), candidate)
+                    .map(|version| (version, Origin::Current)),
+            },
+        };
+        if let Some((version, origin)) = selected {
+            return Ok(Some(Target {
+                candidate: candidate.to_string(),
+                executable: bin_dir(sdkman_dir, candidate, &version).join(name),
+                version,
+                origin,
+            }));
+        }
+    }
+    Ok(None)
+}
+
+// The version a `*_HOME` variable points at, unless it is the `current` link that every shell
+// starts with.
+fn active_version(sdkman_dir: &Path, candidate: &str, home: &str) -> Option<String> {
+    let candidate_dir = sdkman_dir.join(CANDIDATES_DIR).join(candidate);
+    let version = Path::new(home)
+        .strip_prefix(candidate_dir)
+        .ok()?
+        .components()
+        .next()?
+        .as_os_str()
+        .to_string_lossy()
+        .to_string();
+    (version != CURRENT_DIR).then_some(version)
+}
+
+fn bin_dir(sdkman_dir: &Path, candidate: &str, version: &str) -> PathBuf {
+    sdkman_dir
+        .join(CANDIDATES_DIR)
+        .join(candidate)
+        .join(version)
+        .join(BIN_DIR)
+}
+
+/// Finds the executable `name` in `dir`, also trying the suffix that executables have on this
+/// platform, such as `.exe` on Windows.
+pub fn find_executable(dir: &Path, name: &str) -> Option<PathBuf> {
+    [name.to_string(), format!("{}{}", name, EXE_SUFFIX)]
+        .into_iter()
+        .map(|name| dir.join(name))
+        .find(|path| is_executable(path))
+}
+
+#[cfg(unix)]
+pub fn is_executable(path: &Path) -> bool {
+    use std::os::unix::fs::PermissionsExt;
+    fs::metadata(path).is_ok_and(|m| m.is_file() && m.permissions().mode() & 0o111 != 0)
+}
+
+#[cfg(not(unix))]
+pub fn is_executable(path: &Path) -> bool {
+    path.is_file()
+}
+
+#[cfg(test)]
+mod tests {
+    use std::fs;
+    use std::path::Path;
+
+    use tempfile::TempDir;
+
+    use crate::shim::{executables, override_variable, resolve, Origin};
+
+    const CANDIDATES: [&str; 2] = ["java", "maven"];
+
+    fn install(sdkman_dir: &Path, candidate: &str, version: &str, names: &[&str]) {
+        let bin_dir = sdkman_dir
+            .join("candidates")
+            .join(candidate)
+            .join(version)
+            .join("bin");
+        fs::create_dir_all(&bin_dir).unwrap();
+        for name in names {
+            fs::write(bin_dir.join(name), "#!/bin/sh\n").unwrap();
+            #[cfg(unix)]
+            {
+                use std::os::unix::fs::PermissionsExt;
+                fs::set_permissions(bin_dir.join(name), fs::Permissions::from_mode(0o755)).unwrap();
+            }
+        }
+    }
+
+    #[test]
+    fn should_list_executables_of_every_version() {
+        let dir = TempDir::new().unwrap();
+        install(dir.path(), "java", "11.0.15-tem", &["java", "javac"]);
+        install(dir.path(), "java", "17.0.3-tem", &["java", "jshell"]);
+        install(dir.path(), "maven", "3.9.6", &["mvn"]);
+
+        assert_eq!(
+            executables(dir.path(), &CANDIDATES),
+            vec!["java", "javac", "jshell", "mvn"]
+        );
+    }
+
+    #[test]
+    fn should_prefer_override_then_home_then_sdkmanrc_then_current() {
+        let dir = TempDir::new().unwrap();
+        let project = dir.path().join("project");
+        fs::create_dir_all(&project).unwrap();
+        install(dir.path(), "java", "11.0.15-tem", &["java"]);
+        install(dir.path(), "java", "17.0.3-tem", &["java"]);
+        symlink::symlink_dir(
+            dir.path().join("candidates/java/11.0.15-tem"),
+            dir.path().join("candidates/java/current"),
+        )
+        .unwrap();
+        fs::write(project.join(".sdkmanrc"), "java=17.*\n").unwrap();
+
+        let none = |_: &str| None;
+        let target = resolve(dir.path(), dir.path(), "java", &none, &CANDIDATES)
+            .unwrap()
+            .unwrap();
+        assert_eq!(
+            (target.version.as_str(), target.origin),
+            ("11.0.15-tem", Origin::Current)
+        );
+
+        let target = resolve(dir.path(), &project, "java", &none, &CANDIDATES)
+            .unwrap()
+            .unwrap();
+        assert_eq!(target.version, "17.0.3-tem");
+        assert_eq!(target.origin, Origin::Sdkmanrc(project.join(".sdkmanrc")));
+
+        let home = dir.path().join("candidates/java/11.0.15-tem");
+        let used = |name: &str| (name == "JAVA_HOME").then(|| home.display().to_string());
+        let target = resolve(dir.path(), &project, "java", &used, &CANDIDATES)
+            .unwrap()
+            .unwrap();
+        assert_eq!(target.version, "11.0.15-tem");
+        assert_eq!(target.origin, Origin::Home("JAVA_HOME".to_string()));
+
+        let pinned = |name: &str| (name == "SDKMAN_JAVA_VERSION").then(|| "11.0.15-tem".into());
+        let target = resolve(dir.path(), &project, "java", &pinned, &CANDIDATES)
+            .unwrap()
+            .unwrap();
+        assert_eq!(target.origin, Origin::Override(override_variable("java")));
+        assert_eq!(
+            target.executable,
+            dir.path().join("candidates/java/11.0.15-tem/bin/java")
+        );
+
+        assert_eq!(
+            resolve(dir.path(), &project, "gradle", &none, &CANDIDATES).unwrap(),
+            None
+        );
+    }
+}
@@ -0,0 +1,330 @@
+use std::cmp::Ordering;
+use std::fmt;
+
+/// A candidate version identifier such as `17.0.3-tem`, `21.0.2.fx-zulu` or `3.9.6`.
+///
+/// Versions order by their numeric release first, treating missing components as zero. A
+/// qualifier such as `ea` or `RC1` sorts below the plain release, and the vendor suffix only
+/// breaks ties, so that `17.0.10-tem` ranks above `17.0.9-zulu`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Version {
+    pub identifier: String,
+    pub release: Vec<u64>,
+    pub qualifier: Option<String>,
+    pub vendor: Option<String>,
+}
+
+impl Version {
+    pub fn parse(identifier: &str) -> Version {
+        let (body, vendor) = match identifier.rsplit_once('-') {
+            Some((body, suffix)) if is_vendor(suffix) => (body, Some(suffix.to_string())),
+            _ => (identifier, None),
+        };
+        let mut release = Vec::new();
+        let mut rest = Vec::new();
+        for segment in body.split(['.', '-']) {
+            match segment.parse::<u64>() {
+                Ok(number) if rest.is_empty() => release.push(number),
+                _ => rest.push(segment),
+            }
+        }
+        Version {
+            identifier: identifier.to_string(),
+            release,
+            qualifier: (!rest.is_empty()).then(|| rest.join(".")),
+            vendor,
+        }
+    }
+
+    fn cmp_release(&self, other: &Version) -> Ordering {
+        let length = self.release.len().max(other.release.len());
+        (0..length)
+            .map(|i| {
+                let left = self.release.get(i).unwrap_or(&0);
+                let right = other.release.get(i).unwrap_or(&0);
+                left.cmp(right)
+            })
+            .find(|ordering| ordering.is_ne())
+            .unwrap_or(Ordering::Equal)
+    }
+}
+
+// Tags that mark a pre-release, optionally numbered as in rc1 or m2.
+const PRERELEASE_TAGS: [&str; 7] = ["alpha", "beta", "rc", "m", "ea", "snapshot", "preview"];
+
+// Vendor suffixes are short lowercase identifiers such as tem, zulu, graalce or librca. A
+// pre-release tag such as beta or rc1 is a qualifier instead, even when it looks alike.
+fn is_vendor(suffix: &str) -> bool {
+    suffix.starts_with(|c: char| c.is_ascii_lowercase())
+        && suffix
+            .chars()
+            .all(|c| c.is_ascii_lowercase() || c.is_ascii_digit())
+        && !is_prerelease(suffix)
+}
+
+fn is_prerelease(suffix: &str) -> bool {
+    let tag = suffix.trim_end_matches(|c: char| c.is_ascii_digit());
+    PRERELEASE_TAGS.contains(&tag)
+}
+
+// Compares qualifiers part by part, taking the number that ends a part such as rc10 or beta9 by
+// its value, so that rc2 sorts below rc10.
+fn cmp_qualifier(left: &str, right: &str) -> Ordering {
+    let parts = |qualifier: &str| {
+        qualifier
+            .split('.')
+            .map(|part| {
+                let tag = part.trim_end_matches(|c: char| c.is_ascii_digit());
+                (tag.to_string(), part[tag.len()..].parse::<u64>().ok())
+            })
+            .collect::<Vec<_>>()
+    };
+    parts(left).cmp(&parts(right))
+}
+
+impl Ord for Version {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.cmp_release(other)
+            .then_with(|| match (&self.qualifier, &other.qualifier) {
+                (None, None) => Ordering::Equal,
+                (None, Some(_)) => Ordering::Greater,
+                (Some(_), None) => Ordering::Less,
+                (Some(left), Some(right)) => cmp_qualifier(left, right),
+            })
+            .then_with(|| self.vendor.cmp(&other.vendor))
+            .then_with(|| self.identifier.cmp(&other.identifier))
+    }
+}
+
+impl PartialOrd for Version {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl fmt::Display for Version {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.identifier)
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Operator {
+    Greater,
+    GreaterOrEqual,
+    Less,
+    LessOrEqual,
+    Equal,
+}
+
+/// The version requested by an `.sdkmanrc` entry: an exact identifier, a wildcard pattern such
+/// as `21.*-tem`, a lower bound such as `17+`, or comparators such as `>=17 <18`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum VersionReq {
+    Exact(String),
+    Wildcard(String),
+    Comparators(Vec<(Operator, Version)>),
+}
+
+impl VersionReq {
+    pub fn parse(value: &str) -> VersionReq {
+        let value = value.trim();
+        if value.contains('*') {
+            return VersionReq::Wildcard(value.to_string());
+        }
+        if let Some(bound) = value.strip_suffix('+') {
+            return VersionReq::Comparators(vec![(
+                Operator::GreaterOrEqual,
+                Version::parse(bound),
+            )]);
+        }
+        if !value.starts_with(['<', '>', '=']) {
+            return VersionReq::Exact(value.to_string());
+        }
+        let comparators = value
+            .split_whitespace()
+            .map(|comparator| {
+                let (operator, bound) = [
+                    (">=", Operator::GreaterOrEqual),
+                    ("<=", Operator::LessOrEqual),
+                    (">", Operator::Greater),
+                    ("<", Operator::Less),
+                    ("=", Operator::Equal),
+                ]
+                .iter()
+                .find_map(|(prefix, operator)| {
+                    comparator
+                        .strip_prefix(prefix)
+                        .map(|rest| (*operator, rest))
+                })
+                .unwrap_or((Operator::Equal, comparator));
+                (operator, Version::parse(bound))
+            })
+            .collect();
+        VersionReq::Comparators(comparators)
+    }
+
+    pub fn is_exact(&self) -> bool {
+        matches!(self, VersionReq::Exact(_))
+    }
+
+    pub fn matches(&self, version: &Version) -> bool {
+        match self {
+            VersionReq::Exact(identifier) => version.identifier == *identifier,
+            VersionReq::Wildcard(pattern) => glob_matches(pattern, &version.identifier),
+            VersionReq::Comparators(comparators) => comparators.iter().all(|(operator, bound)| {
+                let ordering = version.cmp_release(bound);
+                match operator {
+                    Operator::Greater => ordering.is_gt(),
+                    Operator::GreaterOrEqual => ordering.is_ge(),
+                    Operator::Less => ordering.is_lt(),
+                    Operator::LessOrEqual => ordering.is_le(),
+                    Operator::Equal => ordering.is_eq(),
+                }
+            }),
+        }
+    }
+
+    /// Picks the highest of `versions` that satisfies the requirement.
+    pub fn resolve<'a>(&self, versions: &'a [String]) -> Option<&'a String> {
+        versions
+            .iter()
+            .map(|identifier| (identifier, Version::parse(identifier)))
+            .filter(|(_, version)| self.matches(version))
+            .max_by(|(_, left), (_, right)| left.cmp(right))
+            .map(|(identifier, _)| identifier)
+    }
+}
+
+// A '*' matches any run of characters, everything else matches literally.
+fn glob_matches(pattern: &str, text: &str) -> bool {
+    let mut parts = pattern.split('*');
+    let first = parts.next().unwrap_or_default();
+    let Some(mut rest) = text.strip_prefix(first) else {
+        return false;
+    };
+    let parts = parts.collect::<Vec<_>>();
+    let Some((last, middle)) = parts.split_last() else {
+        return rest.is_empty();
+    };
+    for part in middle {
+        match rest.find(part) {
+            Some(index) => rest = &rest[index + part.len()..],
+            None => return false,
+        }
+    }
+    rest.len() >= last.len() && rest.ends_with(last)
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::version::{Version, VersionReq};
+
+    fn versions(identifiers: &[&str]) -> Vec<String> {
+        identifiers.iter().map(|v| v.to_string()).collect()
+    }
+
+    #[test]
+    fn should_parse_vendor_and_qualifier() {
+        let version = Version::parse("21.0.2.fx-zulu");
+        assert_eq!(version.release, vec![21, 0, 2]);
+        assert_eq!(version.qualifier.as_deref(), Some("fx"));
+        assert_eq!(version.vendor.as_deref(), Some("zulu"));
+
+        let version = Version::parse("4.0.0-RC1");
+        assert_eq!(version.release, vec![4, 0, 0]);
+        assert_eq!(version.qualifier.as_deref(), Some("RC1"));
+        assert_eq!(version.vendor, None);
+    }
+
+    #[test]
+    fn should_order_versions_numerically() {
+        let mut sorted = [
+            "17.0.10-tem",
+            "17.0.9-zulu",
+            "17.0.9-tem",
+            "8.0.392-amzn",
+            "22.ea.1-open",
+            "22-open",
+            "17-tem",
+        ]
+        .map(Version::parse);
+        sorted.sort();
+        let identifiers = sorted.iter().map(|v| v.to_string()).collect::<Vec<_>>();
+        assert_eq!(
+            identifiers,
+            vec![
+                "8.0.392-amzn",
+                "17-tem",
+                "17.0.9-tem",
+                "17.0.9-zulu",
+                "17.0.10-tem",
+                "22.ea.1-open",
+                "22-open",
+            ]
+        );
+    }
+
+    #[test]
+    fn should_order_pre_releases_below_their_release() {
+        let mut sorted = [
+            "3.0.0",
+            "3.0.0-rc1",
+            "2.0.0-beta",
+            "2.0.0",
+            "2.0.0-alpha2",
+            "1.0.0-m3",
+        ]
+        .map(Version::parse);
+        sorted.sort();
+        let identifiers = sorted.iter().map(|v| v.to_string()).collect::<Vec<_>>();
+        assert_eq!(
+            identifiers,
+            vec![
+                "1.0.0-m3",
+                "2.0.0-alpha2",
+                "2.0.0-beta",
+                "2.0.0",
+                "3.0.0-rc1",
+                "3.0.0",
+            ]
+        );
+        assert_eq!(Version::parse("3.0.0-rc1").vendor, None);
+
+        let installed = versions(&["3.0.0-rc1", "3.0.0", "3.1.0-beta"]);
+        let resolve = |value: &str| VersionReq::parse(value).resolve(&installed).cloned();
+        assert_eq!(resolve("3.*").as_deref(), Some("3.1.0-beta"));
+        assert_eq!(resolve("<3.1").as_deref(), Some("3.0.0"));
+    }
+
+    #[test]
+    fn should_order_numbered_pre_releases_by_their_number() {
+        assert!(Version::parse("3.0.0-rc2") < Version::parse("3.0.0-rc10"));
+        assert!(Version::parse("2.0.0-beta9") < Version::parse("2.0.0-beta10"));
+        assert!(Version::parse("2.0.0-beta10") < Version::parse("2.0.0-rc1"));
+
+        let installed = versions(&["3.0.0-rc10", "3.0.0-rc2", "2.0.0-beta9", "2.0.0-beta10"]);
+        let resolve = |value: &str| VersionReq::parse(value).resolve(&installed).cloned();
+        assert_eq!(resolve("3.*").as_deref(), Some("3.0.0-rc10"));
+        assert_eq!(resolve("2.*").as_deref(), Some("2.0.0-beta10"));
+    }
+
+    #[test]
+    fn should_resolve_ranges_to_the_highest_match() {
+        let installed = versions(&[
+            "17.0.3-tem",
+            "17.0.10-tem",
+            "21.0.1-tem",
+            "21.0.2-tem",
+            "21.0.3-zulu",
+        ]);
+        let resolve = |value: &str| VersionReq::parse(value).resolve(&installed).cloned();
+
+        assert_eq!(resolve("21.*-tem").as_deref(), Some("21.0.2-tem"));
+        assert_eq!(resolve(">=17 <18").as_deref(), Some("17.0.10-tem"));
+        assert_eq!(resolve("17+").as_deref(), Some("21.0.3-zulu"));
+        assert_eq!(resolve("17.0.3-tem").as_deref(), Some("17.0.3-tem"));
+        assert_eq!(resolve("11.*"), None);
+        assert!(!VersionReq::parse("21.*-tem").is_exact());
+    }
+}
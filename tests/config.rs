@@ -0,0 +1,175 @@
+#[cfg(test)]
+use assert_cmd::Command;
+use predicates::str::contains;
+use serial_test::serial;
+use std::path::Path;
+use std::{env, fs};
+use support::VirtualEnv;
+
+mod support;
+
+#[test]
+#[serial]
+fn should_set_value_keeping_comments_and_order() -> Result<(), Box<dyn std::error::Error>> {
+    let sdkman_dir = support::virtual_env(VirtualEnv::default());
+    support::write_file(
+        sdkman_dir.path(),
+        Path::new("etc"),
+        "config",
+        "# managed by ansible\nsdkman_auto_answer=false\nsdkman_curl_max_time=10\n".to_string(),
+    );
+
+    env::set_var("SDKMAN_DIR", sdkman_dir.path().as_os_str());
+    Command::new(assert_cmd::cargo::cargo_bin!("config"))
+        .args(["set", "sdkman_auto_answer", "true"])
+        .assert()
+        .success()
+        .stdout(contains("sdkman_auto_answer set to true."))
+        .code(0);
+
+    let content = fs::read_to_string(sdkman_dir.path().join("etc/config"))?;
+    assert_eq!(
+        content,
+        "# managed by ansible\nsdkman_auto_answer=true\nsdkman_curl_max_time=10\n"
+    );
+
+    Command::new(assert_cmd::cargo::cargo_bin!("config"))
+        .args(["get", "sdkman_auto_answer"])
+        .assert()
+        .success()
+        .stdout("true\n")
+        .code(0);
+
+    Ok(())
+}
+
+#[test]
+#[serial]
+fn should_reject_values_of_the_wrong_type() -> Result<(), Box<dyn std::error::Error>> {
+    let sdkman_dir = support::virtual_env(VirtualEnv::default());
+
+    env::set_var("SDKMAN_DIR", sdkman_dir.path().as_os_str());
+    Command::new(assert_cmd::cargo::cargo_bin!("config"))
+        .args(["set", "sdkman_curl_max_time", "ten"])
+        .assert()
+        .failure()
+        .stderr(contains(
+            "invalid value 'ten' for sdkman_curl_max_time, expected a whole number of seconds",
+        ))
+        .code(1);
+
+    Command::new(assert_cmd::cargo::cargo_bin!("config"))
+        .args(["get", "sdkman_colour"])
+        .assert()
+        .failure()
+        .stderr(contains("unknown key sdkman_colour"))
+        .code(1);
+
+    assert!(!sdkman_dir.path().join("etc/config").exists());
+
+    Ok(())
+}
+
+#[test]
+#[serial]
+fn should_list_effective_values_with_their_source() -> Result<(), Box<dyn std::error::Error>> {
+    let sdkman_dir = support::virtual_env(VirtualEnv::default());
+    support::write_file(
+        sdkman_dir.path(),
+        Path::new("etc"),
+        "config",
+        "sdkman_auto_env=true\n".to_string(),
+    );
+
+    env::set_var("SDKMAN_DIR", sdkman_dir.path().as_os_str());
+    Command::new(assert_cmd::cargo::cargo_bin!("config"))
+        .arg("list")
+        .assert()
+        .success()
+        .stdout(contains("sdkman_auto_env=true (file)"))
+        .stdout(contains("sdkman_curl_max_time=10 (default)"))
+        .code(0);
+
+    Ok(())
+}
+
+#[test]
+#[serial]
+fn should_unset_value_to_restore_default() -> Result<(), Box<dyn std::error::Error>> {
+    let sdkman_dir = support::virtual_env(VirtualEnv::default());
+    support::write_file(
+        sdkman_dir.path(),
+        Path::new("etc"),
+        "config",
+        "sdkman_auto_env=true\nsdkman_debug_mode=false\n".to_string(),
+    );
+
+    env::set_var("SDKMAN_DIR", sdkman_dir.path().as_os_str());
+    Command::new(assert_cmd::cargo::cargo_bin!("config"))
+        .args(["unset", "sdkman_auto_env"])
+        .assert()
+        .success()
+        .stdout(contains("sdkman_auto_env reset to its default false."))
+        .code(0);
+
+    let content = fs::read_to_string(sdkman_dir.path().join("etc/config"))?;
+    assert_eq!(content, "sdkman_debug_mode=false\n");
+
+    Ok(())
+}
+
+// A stand-in editor that appends the given line to the file and logs each launch.
+fn fake_editor(dir: &Path, line: &str) -> String {
+    let script = dir.join("editor.sh");
+    let content = format!(
+        "#!/bin/sh\necho launched >> {}\necho '{}' >> \"$1\"\n",
+        dir.join("launches").display(),
+        line
+    );
+    fs::write(&script, content).unwrap();
+    format!("sh {}", script.display())
+}
+
+#[test]
+#[serial]
+fn should_create_default_config_and_open_visual_editor() -> Result<(), Box<dyn std::error::Error>> {
+    let sdkman_dir = support::virtual_env(VirtualEnv::default());
+    let editor = fake_editor(sdkman_dir.path(), "sdkman_auto_answer=true");
+
+    env::set_var("SDKMAN_DIR", sdkman_dir.path().as_os_str());
+    Command::new(assert_cmd::cargo::cargo_bin!("config"))
+        .env("VISUAL", &editor)
+        .env("EDITOR", "false")
+        .assert()
+        .success()
+        .code(0);
+
+    let content = fs::read_to_string(sdkman_dir.path().join("etc/config"))?;
+    assert!(content.starts_with("sdkman_auto_answer=false\n"));
+    assert!(content.ends_with("sdkman_selfupdate_feature=true\nsdkman_auto_answer=true\n"));
+
+    Ok(())
+}
+
+#[test]
+#[serial]
+fn should_reopen_editor_while_config_does_not_validate() -> Result<(), Box<dyn std::error::Error>> {
+    let sdkman_dir = support::virtual_env(VirtualEnv::default());
+    let editor = fake_editor(sdkman_dir.path(), "sdkman_colour_enabel=false");
+
+    env::set_var("SDKMAN_DIR", sdkman_dir.path().as_os_str());
+    Command::new(assert_cmd::cargo::cargo_bin!("config"))
+        .env_remove("VISUAL")
+        .env("EDITOR", &editor)
+        .write_stdin("y\nn\n")
+        .assert()
+        .failure()
+        .stderr(contains("line 14: unknown key sdkman_colour_enabel"))
+        .stdout(contains("reopen the editor"))
+        .code(1);
+
+    let launches = fs::read_to_string(sdkman_dir.path().join("launches"))?;
+    assert_eq!(launches.lines().count(), 2);
+
+    Ok(())
+}
@@ -191,3 +191,38 @@ fn should_show_message_when_no_candidates_in_use() -> Result<(), Box<dyn std::er
 
     Ok(())
 }
+
+#[test]
+#[serial]
+fn should_not_colour_output_when_colour_is_disabled() -> Result<(), Box<dyn std::error::Error>> {
+    let env = VirtualEnv {
+        cli_version: "5.0.0".to_string(),
+        native_version: "0.1.0".to_string(),
+        candidates: vec![TestCandidate {
+            name: "java",
+            versions: vec!["17.0.3-tem"],
+            current_version: "17.0.3-tem",
+        }],
+    };
+
+    let sdkman_dir = support::virtual_env(env);
+    support::write_file(
+        sdkman_dir.path(),
+        Path::new("etc"),
+        "config",
+        "sdkman_colour_enable=false\n".to_string(),
+    );
+    env::set_var("SDKMAN_DIR", sdkman_dir.path().as_os_str());
+
+    Command::new(assert_cmd::cargo::cargo_bin!("current"))
+        .arg("java")
+        .env("CLICOLOR_FORCE", "1")
+        .assert()
+        .success()
+        .stdout(predicate::str::contains(
+            "Current default java version 17.0.3-tem",
+        ))
+        .stdout(predicate::str::contains("\u{1b}[").not());
+
+    Ok(())
+}
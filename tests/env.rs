@@ -0,0 +1,701 @@
+#[cfg(test)]
+use assert_cmd::Command;
+use flate2::write::GzEncoder;
+use flate2::Compression;
+use predicates::prelude::*;
+use predicates::str::contains;
+use serial_test::serial;
+use sha2::{Digest, Sha256};
+use std::path::Path;
+use std::{env, fs, thread};
+use support::{TestCandidate, VirtualEnv};
+use tiny_http::{Header, Response, Server};
+
+mod support;
+
+fn project_env() -> VirtualEnv {
+    VirtualEnv {
+        cli_version: "5.0.0".to_string(),
+        native_version: "0.1.0".to_string(),
+        candidates: vec![
+            TestCandidate {
+                name: "java",
+                versions: vec!["11.0.15-tem", "17.0.3-tem"],
+                current_version: "11.0.15-tem",
+            },
+            TestCandidate {
+                name: "maven",
+                versions: vec!["3.9.6"],
+                current_version: "3.9.6",
+            },
+        ],
+    }
+}
+
+#[test]
+#[serial]
+fn should_print_exports_for_sdkmanrc_versions() -> Result<(), Box<dyn std::error::Error>> {
+    let sdkman_dir = support::virtual_env(project_env());
+    let project_dir = support::prepare_sdkman_dir();
+    support::write_file(
+        project_dir.path(),
+        Path::new(""),
+        ".sdkmanrc",
+        "# project sdks\njava=17.0.3-tem\n".to_string(),
+    );
+    let candidates = sdkman_dir.path().join("candidates");
+    let path = format!(
+        "/usr/bin:{}:{}",
+        candidates.join("java/current/bin").display(),
+        candidates.join("maven/current/bin").display()
+    );
+
+    env::set_var("SDKMAN_DIR", sdkman_dir.path().as_os_str());
+    Command::new(assert_cmd::cargo::cargo_bin!("env"))
+        .env("SHELL", "/bin/bash")
+        .current_dir(project_dir.path())
+        .env("PATH", &path)
+        .assert()
+        .success()
+        .stdout(format!(
+            "export PATH='/usr/bin:{}:{}'\nexport JAVA_HOME='{}'\n",
+            candidates.join("java/17.0.3-tem/bin").display(),
+            candidates.join("maven/current/bin").display(),
+            candidates.join("java/17.0.3-tem").display()
+        ))
+        .stderr(contains("Using java version 17.0.3-tem in this shell."))
+        .code(0);
+
+    Ok(())
+}
+
+#[test]
+#[serial]
+fn should_warn_about_versions_not_installed() -> Result<(), Box<dyn std::error::Error>> {
+    let sdkman_dir = support::virtual_env(project_env());
+    let project_dir = support::prepare_sdkman_dir();
+    support::write_file(
+        project_dir.path(),
+        Path::new(""),
+        ".sdkmanrc",
+        "java=21.0.2-tem\nmaven=3.9.6\n".to_string(),
+    );
+
+    env::set_var("SDKMAN_DIR", sdkman_dir.path().as_os_str());
+    Command::new(assert_cmd::cargo::cargo_bin!("env"))
+        .env("SHELL", "/bin/bash")
+        .current_dir(project_dir.path())
+        .env("PATH", "/usr/bin")
+        .assert()
+        .success()
+        .stderr(contains("Stop! java 21.0.2-tem is not installed."))
+        .stdout(contains("export MAVEN_HOME="))
+        .stdout(contains("JAVA_HOME").not())
+        .code(0);
+
+    Ok(())
+}
+
+#[test]
+#[serial]
+fn should_fail_without_sdkmanrc() -> Result<(), Box<dyn std::error::Error>> {
+    let sdkman_dir = support::virtual_env(project_env());
+    let project_dir = support::prepare_sdkman_dir();
+
+    env::set_var("SDKMAN_DIR", sdkman_dir.path().as_os_str());
+    Command::new(assert_cmd::cargo::cargo_bin!("env"))
+        .current_dir(project_dir.path())
+        .assert()
+        .failure()
+        .stderr(contains(
+            "Could not find .sdkmanrc in the current directory or its parents.",
+        ))
+        .stdout("")
+        .code(1);
+
+    Ok(())
+}
+
+#[test]
+#[serial]
+fn should_report_sdkmanrc_errors_with_line() -> Result<(), Box<dyn std::error::Error>> {
+    let sdkman_dir = support::virtual_env(project_env());
+    let project_dir = support::prepare_sdkman_dir();
+    support::write_file(
+        project_dir.path(),
+        Path::new(""),
+        ".sdkmanrc",
+        "java=17.0.3-tem\ngradel=8.5\n".to_string(),
+    );
+
+    env::set_var("SDKMAN_DIR", sdkman_dir.path().as_os_str());
+    Command::new(assert_cmd::cargo::cargo_bin!("env"))
+        .current_dir(project_dir.path())
+        .assert()
+        .failure()
+        .stderr(contains(".sdkmanrc:2: gradel is not a valid candidate"))
+        .code(1);
+
+    Ok(())
+}
+
+#[test]
+#[serial]
+fn should_init_sdkmanrc_with_every_current_default() -> Result<(), Box<dyn std::error::Error>> {
+    let sdkman_dir = support::virtual_env(project_env());
+    let project_dir = support::prepare_sdkman_dir();
+
+    env::set_var("SDKMAN_DIR", sdkman_dir.path().as_os_str());
+    Command::new(assert_cmd::cargo::cargo_bin!("env"))
+        .arg("init")
+        .current_dir(project_dir.path())
+        .assert()
+        .success()
+        .stderr(contains(".sdkmanrc created."))
+        .stdout("")
+        .code(0);
+
+    let content = fs::read_to_string(project_dir.path().join(".sdkmanrc"))?;
+    assert_eq!(
+        content,
+        "# Enable auto-env through the sdkman_auto_env config\n\
+         # Add key=value pairs of SDKs to use below\n\
+         java=11.0.15-tem\n\
+         maven=3.9.6\n"
+    );
+
+    Ok(())
+}
+
+#[test]
+#[serial]
+fn should_init_sdkmanrc_with_version_of_copied_current_directory(
+) -> Result<(), Box<dyn std::error::Error>> {
+    let sdkman_dir = support::virtual_env(project_env());
+    let project_dir = support::prepare_sdkman_dir();
+    let candidates = sdkman_dir.path().join("candidates");
+    fs::remove_file(candidates.join("java/current"))?;
+    fs::create_dir_all(candidates.join("java/current/bin"))?;
+    fs::copy(
+        candidates.join("java/17.0.3-tem/bin/java"),
+        candidates.join("java/current/bin/java"),
+    )?;
+    fs::remove_file(candidates.join("maven/current"))?;
+    fs::create_dir_all(candidates.join("maven/current/bin"))?;
+    fs::write(candidates.join("maven/current/bin/mvn"), "unknown")?;
+
+    env::set_var("SDKMAN_DIR", sdkman_dir.path().as_os_str());
+    Command::new(assert_cmd::cargo::cargo_bin!("env"))
+        .arg("init")
+        .current_dir(project_dir.path())
+        .assert()
+        .success()
+        .stderr(contains(
+            "Cannot tell which version of maven the current directory is, skipping.",
+        ))
+        .code(0);
+
+    let content = fs::read_to_string(project_dir.path().join(".sdkmanrc"))?;
+    assert!(content.ends_with("below\njava=17.0.3-tem\n"));
+
+    Ok(())
+}
+
+#[test]
+#[serial]
+fn should_init_only_selected_candidates_when_forced() -> Result<(), Box<dyn std::error::Error>> {
+    let sdkman_dir = support::virtual_env(project_env());
+    let project_dir = support::prepare_sdkman_dir();
+    support::write_file(
+        project_dir.path(),
+        Path::new(""),
+        ".sdkmanrc",
+        "java=17.0.3-tem\n".to_string(),
+    );
+
+    env::set_var("SDKMAN_DIR", sdkman_dir.path().as_os_str());
+    Command::new(assert_cmd::cargo::cargo_bin!("env"))
+        .args(["init", "--only", "maven"])
+        .current_dir(project_dir.path())
+        .assert()
+        .failure()
+        .stderr(contains(
+            ".sdkmanrc already exists! Use --force to overwrite it.",
+        ))
+        .code(1);
+
+    Command::new(assert_cmd::cargo::cargo_bin!("env"))
+        .args(["init", "--only", "maven", "--force"])
+        .current_dir(project_dir.path())
+        .assert()
+        .success()
+        .code(0);
+
+    let content = fs::read_to_string(project_dir.path().join(".sdkmanrc"))?;
+    assert!(content.ends_with("below\nmaven=3.9.6\n"));
+
+    Ok(())
+}
+
+// Serves a maven archive with its checksum for every version but 21.0.2-tem, which the broker
+// does not know.
+fn serve_broker() -> String {
+    let launcher = "#!/bin/bash\necho Running mvn\n";
+    let mut header = tar::Header::new_gnu();
+    header.set_size(launcher.len() as u64);
+    header.set_mode(0o755);
+    header.set_cksum();
+    let mut builder = tar::Builder::new(GzEncoder::new(Vec::new(), Compression::fast()));
+    builder
+        .append_data(&mut header, "apache-maven/bin/mvn", launcher.as_bytes())
+        .unwrap();
+    let archive = builder.into_inner().unwrap().finish().unwrap();
+    let sha256 = hex::encode(Sha256::digest(&archive));
+
+    let server = Server::http("127.0.0.1:0").unwrap();
+    let url = format!("http://{}", server.server_addr().to_ip().unwrap());
+    thread::spawn(move || {
+        for request in server.incoming_requests() {
+            let unknown = request.url().contains("21.0.2-tem");
+            let sidecar = [".sha512", ".sha256", ".md5"]
+                .iter()
+                .any(|extension| request.url().ends_with(extension));
+            let response = if unknown || sidecar {
+                Response::from_data(Vec::new()).with_status_code(404)
+            } else {
+                Response::from_data(archive.clone())
+                    .with_header(Header::from_bytes("X-Sdkman-ArchiveType", "tar").unwrap())
+                    .with_header(
+                        Header::from_bytes("X-Sdkman-Checksum-SHA-256", sha256.as_str()).unwrap(),
+                    )
+            };
+            request.respond(response).unwrap();
+        }
+    });
+    url
+}
+
+#[test]
+#[serial]
+fn should_install_missing_versions_and_report_summary() -> Result<(), Box<dyn std::error::Error>> {
+    let sdkman_dir = support::virtual_env(project_env());
+    let project_dir = support::prepare_sdkman_dir();
+    support::write_file(
+        project_dir.path(),
+        Path::new(""),
+        ".sdkmanrc",
+        "java=21.0.2-tem\nmaven=3.9.9\n".to_string(),
+    );
+    let url = serve_broker();
+
+    env::set_var("SDKMAN_DIR", sdkman_dir.path().as_os_str());
+    Command::new(assert_cmd::cargo::cargo_bin!("env"))
+        .env("SHELL", "/bin/bash")
+        .arg("install")
+        .current_dir(project_dir.path())
+        .env("SDKMAN_CANDIDATES_API", &url)
+        .env("PATH", "/usr/bin")
+        .assert()
+        .failure()
+        .stderr(contains("cannot install java 21.0.2-tem"))
+        .stderr(contains("installed maven 3.9.9."))
+        .stderr(contains("1 installed, 0 skipped, 1 failed."))
+        .stdout(contains("export MAVEN_HOME="))
+        .code(1);
+
+    assert!(sdkman_dir
+        .path()
+        .join("candidates/maven/3.9.9/bin/mvn")
+        .is_file());
+
+    Ok(())
+}
+
+#[test]
+#[serial]
+fn should_skip_versions_already_installed() -> Result<(), Box<dyn std::error::Error>> {
+    let sdkman_dir = support::virtual_env(project_env());
+    let project_dir = support::prepare_sdkman_dir();
+    support::write_file(
+        project_dir.path(),
+        Path::new(""),
+        ".sdkmanrc",
+        "java=17.0.3-tem\nmaven=3.9.6\n".to_string(),
+    );
+
+    env::set_var("SDKMAN_DIR", sdkman_dir.path().as_os_str());
+    Command::new(assert_cmd::cargo::cargo_bin!("env"))
+        .env("SHELL", "/bin/bash")
+        .arg("install")
+        .current_dir(project_dir.path())
+        .env("PATH", "/usr/bin")
+        .assert()
+        .success()
+        .stderr(contains("java 17.0.3-tem is already installed, skipping."))
+        .stderr(contains("0 installed, 2 skipped, 0 failed."))
+        .stdout(contains("export JAVA_HOME="))
+        .code(0);
+
+    Ok(())
+}
+
+#[test]
+#[serial]
+fn should_clear_project_versions_from_the_shell() -> Result<(), Box<dyn std::error::Error>> {
+    let sdkman_dir = support::virtual_env(project_env());
+    let candidates = sdkman_dir.path().join("candidates");
+    fs::remove_file(candidates.join("maven/current"))?;
+    let path = format!(
+        "{}:/usr/bin:{}",
+        candidates.join("java/17.0.3-tem/bin").display(),
+        candidates.join("maven/3.9.6/bin").display()
+    );
+
+    env::set_var("SDKMAN_DIR", sdkman_dir.path().as_os_str());
+    Command::new(assert_cmd::cargo::cargo_bin!("env"))
+        .env("SHELL", "/bin/bash")
+        .arg("clear")
+        .env("PATH", &path)
+        .env("JAVA_HOME", candidates.join("java/17.0.3-tem"))
+        .env("MAVEN_HOME", candidates.join("maven/3.9.6"))
+        .assert()
+        .success()
+        .stdout(format!(
+            "export PATH='{}:/usr/bin'\nexport JAVA_HOME='{}'\nunset MAVEN_HOME\n",
+            candidates.join("java/current/bin").display(),
+            candidates.join("java/current").display()
+        ))
+        .stderr(contains("Restored java to its default version."))
+        .stderr(contains("Removed maven from this shell"))
+        .code(0);
+
+    Ok(())
+}
+
+#[test]
+#[serial]
+fn should_check_sdkmanrc_against_the_shell() -> Result<(), Box<dyn std::error::Error>> {
+    let sdkman_dir = support::virtual_env(project_env());
+    let project_dir = support::prepare_sdkman_dir();
+    support::write_file(
+        project_dir.path(),
+        Path::new(""),
+        ".sdkmanrc",
+        "java=17.0.3-tem\nmaven=3.9.6\n".to_string(),
+    );
+    let candidates = sdkman_dir.path().join("candidates");
+    let active_path = format!(
+        "{}:{}",
+        candidates.join("java/17.0.3-tem/bin").display(),
+        candidates.join("maven/current/bin").display()
+    );
+    let default_path = format!(
+        "{}:{}",
+        candidates.join("java/current/bin").display(),
+        candidates.join("maven/current/bin").display()
+    );
+
+    env::set_var("SDKMAN_DIR", sdkman_dir.path().as_os_str());
+    Command::new(assert_cmd::cargo::cargo_bin!("env"))
+        .arg("check")
+        .current_dir(project_dir.path())
+        .env("PATH", &active_path)
+        .env_remove("JAVA_HOME")
+        .assert()
+        .success()
+        .stdout(contains(
+            "CANDIDATE  EXPECTED    INSTALLED  ACTIVE      STATUS",
+        ))
+        .stdout(contains("java       17.0.3-tem  yes        17.0.3-tem  ok"))
+        .code(0);
+
+    Command::new(assert_cmd::cargo::cargo_bin!("env"))
+        .arg("check")
+        .current_dir(project_dir.path())
+        .env("PATH", &default_path)
+        .env_remove("JAVA_HOME")
+        .assert()
+        .failure()
+        .stdout(contains(
+            "java       17.0.3-tem  yes        11.0.15-tem  inactive",
+        ))
+        .code(3);
+
+    Ok(())
+}
+
+#[test]
+#[serial]
+fn should_check_missing_versions_with_own_exit_code() -> Result<(), Box<dyn std::error::Error>> {
+    let sdkman_dir = support::virtual_env(project_env());
+    let project_dir = support::prepare_sdkman_dir();
+    support::write_file(
+        project_dir.path(),
+        Path::new(""),
+        ".sdkmanrc",
+        "java=17.0.3-tem\nmaven=3.9.9\n".to_string(),
+    );
+
+    env::set_var("SDKMAN_DIR", sdkman_dir.path().as_os_str());
+    Command::new(assert_cmd::cargo::cargo_bin!("env"))
+        .arg("check")
+        .current_dir(project_dir.path())
+        .env("PATH", "/usr/bin")
+        .env_remove("JAVA_HOME")
+        .assert()
+        .failure()
+        .stdout(contains(
+            "maven      3.9.9       no         -       missing",
+        ))
+        .code(4);
+
+    Ok(())
+}
+
+#[test]
+#[serial]
+fn should_resolve_version_ranges_to_installed_versions() -> Result<(), Box<dyn std::error::Error>> {
+    let sdkman_dir = support::virtual_env(project_env());
+    let project_dir = support::prepare_sdkman_dir();
+    support::write_file(
+        project_dir.path(),
+        Path::new(""),
+        ".sdkmanrc",
+        "java=>=11 <17\nmaven=3.*\n".to_string(),
+    );
+    let candidates = sdkman_dir.path().join("candidates");
+
+    env::set_var("SDKMAN_DIR", sdkman_dir.path().as_os_str());
+    Command::new(assert_cmd::cargo::cargo_bin!("env"))
+        .env("SHELL", "/bin/bash")
+        .current_dir(project_dir.path())
+        .env("PATH", "/usr/bin")
+        .assert()
+        .success()
+        .stderr(contains("Resolved java >=11 <17 to 11.0.15-tem."))
+        .stderr(contains("Resolved maven 3.* to 3.9.6."))
+        .stdout(contains(format!(
+            "export JAVA_HOME='{}'",
+            candidates.join("java/11.0.15-tem").display()
+        )))
+        .code(0);
+
+    Ok(())
+}
+
+#[test]
+#[serial]
+fn should_merge_sdkmanrc_files_of_parent_directories() -> Result<(), Box<dyn std::error::Error>> {
+    let sdkman_dir = support::virtual_env(project_env());
+    let repo_dir = support::prepare_sdkman_dir();
+    fs::create_dir_all(repo_dir.path().join(".git"))?;
+    support::write_file(
+        repo_dir.path(),
+        Path::new(""),
+        ".sdkmanrc",
+        "java=11.0.15-tem\nmaven=3.9.6\n".to_string(),
+    );
+    let module_rc = support::write_file(
+        repo_dir.path(),
+        Path::new("services/api"),
+        ".sdkmanrc",
+        "java=17.0.3-tem\n".to_string(),
+    );
+    let module_dir = repo_dir.path().join("services/api");
+    let candidates = sdkman_dir.path().join("candidates");
+
+    env::set_var("SDKMAN_DIR", sdkman_dir.path().as_os_str());
+    Command::new(assert_cmd::cargo::cargo_bin!("env"))
+        .env("SHELL", "/bin/bash")
+        .current_dir(&module_dir)
+        .env("PATH", "/usr/bin")
+        .assert()
+        .success()
+        .stdout(contains(format!(
+            "export JAVA_HOME='{}'",
+            candidates.join("java/17.0.3-tem").display()
+        )))
+        .stdout(contains(format!(
+            "export MAVEN_HOME='{}'",
+            candidates.join("maven/3.9.6").display()
+        )))
+        .code(0);
+
+    Command::new(assert_cmd::cargo::cargo_bin!("env"))
+        .arg("sources")
+        .current_dir(&module_dir)
+        .assert()
+        .success()
+        .stdout(contains(format!("{}:1", module_rc.display())))
+        .stdout(contains(format!(
+            "{}:2",
+            repo_dir.path().join(".sdkmanrc").display()
+        )))
+        .code(0);
+
+    Ok(())
+}
+
+#[test]
+#[serial]
+fn should_clear_versions_in_the_requested_shell() -> Result<(), Box<dyn std::error::Error>> {
+    let sdkman_dir = support::virtual_env(project_env());
+    let candidates = sdkman_dir.path().join("candidates");
+    fs::remove_file(candidates.join("maven/current"))?;
+
+    env::set_var("SDKMAN_DIR", sdkman_dir.path().as_os_str());
+    Command::new(assert_cmd::cargo::cargo_bin!("env"))
+        .args(["clear", "--shell", "pwsh"])
+        .env("PATH", candidates.join("maven/3.9.6/bin"))
+        .env("MAVEN_HOME", candidates.join("maven/3.9.6"))
+        .env_remove("JAVA_HOME")
+        .assert()
+        .success()
+        .stdout("$env:PATH = ''\nRemove-Item -ErrorAction SilentlyContinue Env:MAVEN_HOME\n")
+        .code(0);
+
+    Ok(())
+}
+
+#[test]
+#[serial]
+fn should_print_exports_that_bash_can_evaluate() -> Result<(), Box<dyn std::error::Error>> {
+    let sdkman_dir = support::virtual_env(project_env());
+    let project_dir = support::prepare_sdkman_dir();
+    support::write_file(
+        project_dir.path(),
+        Path::new(""),
+        ".sdkmanrc",
+        "java=17.0.3-tem\n".to_string(),
+    );
+
+    env::set_var("SDKMAN_DIR", sdkman_dir.path().as_os_str());
+    let output = Command::new(assert_cmd::cargo::cargo_bin!("env"))
+        .args(["--shell", "bash"])
+        .current_dir(project_dir.path())
+        .env("PATH", "/usr/bin:/bin")
+        .output()?;
+    let script = format!(
+        "{}printf '%s' \"$JAVA_HOME\"",
+        String::from_utf8(output.stdout)?
+    );
+
+    std::process::Command::new("bash")
+        .args(["-c", &script])
+        .env("PATH", "/usr/bin:/bin")
+        .output()
+        .map(|out| {
+            assert_eq!(
+                String::from_utf8_lossy(&out.stdout),
+                sdkman_dir
+                    .path()
+                    .join("candidates/java/17.0.3-tem")
+                    .display()
+                    .to_string()
+            )
+        })?;
+
+    Ok(())
+}
+
+#[test]
+#[serial]
+fn should_print_only_changes_when_entering_and_leaving_project(
+) -> Result<(), Box<dyn std::error::Error>> {
+    let sdkman_dir = support::virtual_env(project_env());
+    let project_dir = support::prepare_sdkman_dir();
+    let outside_dir = support::prepare_sdkman_dir();
+    support::write_file(
+        project_dir.path(),
+        Path::new(""),
+        ".sdkmanrc",
+        "java=17.0.3-tem\nmaven=3.9.6\n".to_string(),
+    );
+    let sdkmanrc = project_dir.path().join(".sdkmanrc");
+    let candidates = sdkman_dir.path().join("candidates");
+    let maven_bin = candidates.join("maven/3.9.6/bin");
+    let default_path = format!(
+        "{}:{}:/usr/bin",
+        candidates.join("java/current/bin").display(),
+        maven_bin.display()
+    );
+    let project_path = format!(
+        "{}:{}:/usr/bin",
+        candidates.join("java/17.0.3-tem/bin").display(),
+        maven_bin.display()
+    );
+
+    env::set_var("SDKMAN_DIR", sdkman_dir.path().as_os_str());
+    Command::new(assert_cmd::cargo::cargo_bin!("env"))
+        .args(["hook", "--shell", "bash"])
+        .arg(outside_dir.path())
+        .current_dir(project_dir.path())
+        .env("PATH", &default_path)
+        .env("JAVA_HOME", candidates.join("java/current"))
+        .env("MAVEN_HOME", candidates.join("maven/3.9.6"))
+        .env_remove("SDKMAN_ENV")
+        .assert()
+        .success()
+        .stdout(format!(
+            "export PATH='{}'\nexport JAVA_HOME='{}'\nexport SDKMAN_ENV='{}'\n",
+            project_path,
+            candidates.join("java/17.0.3-tem").display(),
+            sdkmanrc.display()
+        ))
+        .code(0);
+
+    Command::new(assert_cmd::cargo::cargo_bin!("env"))
+        .args(["hook", "--shell", "bash"])
+        .arg(project_dir.path())
+        .current_dir(outside_dir.path())
+        .env("PATH", &project_path)
+        .env("JAVA_HOME", candidates.join("java/17.0.3-tem"))
+        .env("MAVEN_HOME", candidates.join("maven/3.9.6"))
+        .env("SDKMAN_ENV", &sdkmanrc)
+        .assert()
+        .success()
+        .stdout(format!(
+            "export PATH='{}:{}:/usr/bin'\nexport JAVA_HOME='{}'\nexport MAVEN_HOME='{}'\nunset SDKMAN_ENV\n",
+            candidates.join("java/current/bin").display(),
+            candidates.join("maven/current/bin").display(),
+            candidates.join("java/current").display(),
+            candidates.join("maven/current").display()
+        ))
+        .code(0);
+
+    Ok(())
+}
+
+#[test]
+#[serial]
+fn should_print_nothing_when_staying_in_project() -> Result<(), Box<dyn std::error::Error>> {
+    let sdkman_dir = support::virtual_env(project_env());
+    let project_dir = support::prepare_sdkman_dir();
+    support::write_file(
+        project_dir.path(),
+        Path::new(""),
+        ".sdkmanrc",
+        "java=17.0.3-tem\n".to_string(),
+    );
+    let module_dir = project_dir.path().join("module/src");
+    fs::create_dir_all(&module_dir)?;
+    let sdkmanrc = project_dir.path().join(".sdkmanrc");
+
+    env::set_var("SDKMAN_DIR", sdkman_dir.path().as_os_str());
+    for (previous, current) in [
+        (project_dir.path(), module_dir.as_path()),
+        (module_dir.as_path(), project_dir.path()),
+    ] {
+        Command::new(assert_cmd::cargo::cargo_bin!("env"))
+            .args(["hook", "--shell", "bash"])
+            .arg(previous)
+            .current_dir(current)
+            .env("SDKMAN_ENV", &sdkmanrc)
+            .assert()
+            .success()
+            .stdout("")
+            .code(0);
+    }
+
+    Ok(())
+}
@@ -0,0 +1,147 @@
+#![cfg(unix)]
+
+#[cfg(test)]
+use assert_cmd::Command;
+use predicates::str::contains;
+use serial_test::serial;
+use std::env;
+use std::fs;
+use std::os::unix::fs::PermissionsExt;
+use support::{TestCandidate, VirtualEnv};
+
+mod support;
+
+fn project_env() -> VirtualEnv {
+    VirtualEnv {
+        cli_version: "5.0.0".to_string(),
+        native_version: "0.1.0".to_string(),
+        candidates: vec![
+            TestCandidate {
+                name: "java",
+                versions: vec!["11.0.15-tem", "17.0.3-tem"],
+                current_version: "11.0.15-tem",
+            },
+            TestCandidate {
+                name: "maven",
+                versions: vec!["3.9.6"],
+                current_version: "3.9.6",
+            },
+        ],
+    }
+}
+
+#[test]
+#[serial]
+fn should_run_command_with_selected_version() -> Result<(), Box<dyn std::error::Error>> {
+    let sdkman_dir = support::virtual_env(project_env());
+    let java = sdkman_dir.path().join("candidates/java");
+    fs::set_permissions(
+        java.join("17.0.3-tem/bin/java"),
+        fs::Permissions::from_mode(0o755),
+    )?;
+    let path = format!("{}:/usr/bin:/bin", java.join("current/bin").display());
+
+    env::set_var("SDKMAN_DIR", sdkman_dir.path().as_os_str());
+    Command::new(assert_cmd::cargo::cargo_bin!("exec"))
+        .args([
+            "java",
+            "17.0.3-tem",
+            "--",
+            "sh",
+            "-c",
+            "echo $JAVA_HOME; java",
+        ])
+        .env("PATH", &path)
+        .assert()
+        .success()
+        .stdout(format!(
+            "{}\nRunning java 17.0.3-tem\n",
+            java.join("17.0.3-tem").display()
+        ))
+        .code(0);
+
+    Ok(())
+}
+
+#[test]
+#[serial]
+fn should_accept_several_versions_and_pass_exit_status_through(
+) -> Result<(), Box<dyn std::error::Error>> {
+    let sdkman_dir = support::virtual_env(project_env());
+    let candidates = sdkman_dir.path().join("candidates");
+
+    env::set_var("SDKMAN_DIR", sdkman_dir.path().as_os_str());
+    Command::new(assert_cmd::cargo::cargo_bin!("exec"))
+        .args(["java=17.0.3-tem", "maven=3.9.6", "--", "sh", "-c"])
+        .arg("echo $JAVA_HOME $MAVEN_HOME; exit 3")
+        .env("PATH", "/usr/bin:/bin")
+        .assert()
+        .failure()
+        .stdout(format!(
+            "{} {}\n",
+            candidates.join("java/17.0.3-tem").display(),
+            candidates.join("maven/3.9.6").display()
+        ))
+        .code(3);
+
+    Ok(())
+}
+
+#[test]
+#[serial]
+fn should_fail_for_version_not_installed() -> Result<(), Box<dyn std::error::Error>> {
+    let sdkman_dir = support::virtual_env(project_env());
+
+    env::set_var("SDKMAN_DIR", sdkman_dir.path().as_os_str());
+    Command::new(assert_cmd::cargo::cargo_bin!("exec"))
+        .args(["java", "21.0.2-tem", "--", "true"])
+        .assert()
+        .failure()
+        .stderr(contains("java 21.0.2-tem is not installed on your system"))
+        .code(1);
+
+    Command::new(assert_cmd::cargo::cargo_bin!("exec"))
+        .args(["java", "17.0.3-tem", "--", "no-such-command-for-sdk-exec"])
+        .assert()
+        .failure()
+        .stderr(contains("cannot run no-such-command-for-sdk-exec"))
+        .code(127);
+
+    Ok(())
+}
+
+#[test]
+#[serial]
+fn should_run_matrix_and_summarise_results() -> Result<(), Box<dyn std::error::Error>> {
+    let sdkman_dir = support::virtual_env(project_env());
+
+    env::set_var("SDKMAN_DIR", sdkman_dir.path().as_os_str());
+    for parallel in [false, true] {
+        let mut command = Command::new(assert_cmd::cargo::cargo_bin!("exec"));
+        if parallel {
+            command.arg("--parallel");
+        }
+        command
+            .args([
+                "--matrix",
+                "java=11.0.15-tem,17.*,21.0.2-tem",
+                "maven=3.9.6",
+            ])
+            .args(["--", "sh", "-c"])
+            .arg("basename $JAVA_HOME; test $(basename $JAVA_HOME) = 11.0.15-tem")
+            .env("PATH", "/usr/bin:/bin")
+            .env("NO_COLOR", "1")
+            .assert()
+            .failure()
+            .stdout(contains("[java 11.0.15-tem] 11.0.15-tem\n"))
+            .stdout(contains("[java 17.0.3-tem] 17.0.3-tem\n"))
+            .stderr(contains("[java 21.0.2-tem] is not installed, skipping."))
+            .stdout(contains("CANDIDATE  VERSION      RESULT         TIME\n"))
+            .stdout(contains("java       11.0.15-tem  pass"))
+            .stdout(contains("java       17.0.3-tem   fail (exit 1)"))
+            .stdout(contains("java       21.0.2-tem   not installed  -\n"))
+            .code(1);
+    }
+
+    Ok(())
+}
@@ -0,0 +1,160 @@
+#[cfg(test)]
+use assert_cmd::Command;
+use predicates::str::contains;
+use serial_test::serial;
+use std::env;
+use std::fs::File;
+use std::path::Path;
+use std::time::{Duration, SystemTime};
+use support::VirtualEnv;
+
+mod support;
+
+// Partial downloads are only flushed once no install has written to them for a while.
+fn backdate(path: &Path) {
+    File::options()
+        .write(true)
+        .open(path)
+        .and_then(|file| file.set_modified(SystemTime::now() - Duration::from_secs(2 * 60 * 60)))
+        .expect("cannot backdate file");
+}
+
+#[test]
+#[serial]
+fn should_flush_archives_and_partial_downloads_but_keep_quarantine_in_tmp(
+) -> Result<(), Box<dyn std::error::Error>> {
+    let sdkman_dir = support::virtual_env(VirtualEnv::default());
+    let tmp = Path::new("tmp");
+    backdate(&support::write_file(
+        sdkman_dir.path(),
+        tmp,
+        "java-17.bin.part",
+        "x".to_string(),
+    ));
+    support::write_file(sdkman_dir.path(), tmp, "scala-3.zip", "x".to_string());
+    support::write_file(
+        sdkman_dir.path(),
+        Path::new("tmp/quarantine"),
+        "java-11.zip",
+        "x".to_string(),
+    );
+
+    env::set_var("SDKMAN_DIR", sdkman_dir.path().as_os_str());
+    Command::new(assert_cmd::cargo::cargo_bin!("flush"))
+        .arg("tmp")
+        .assert()
+        .success()
+        .stdout(contains(
+            "2 item(s) flushed from tmp, including 1 partial download(s).\n\
+             1 quarantined archive(s) kept in tmp/quarantine.",
+        ))
+        .code(0);
+
+    let tmp_dir = sdkman_dir.path().join("tmp");
+    assert!(!tmp_dir.join("java-17.bin.part").exists());
+    assert!(!tmp_dir.join("scala-3.zip").exists());
+    assert!(tmp_dir.join("quarantine/java-11.zip").is_file());
+
+    Ok(())
+}
+
+#[test]
+#[serial]
+fn should_keep_partial_downloads_that_may_still_be_written(
+) -> Result<(), Box<dyn std::error::Error>> {
+    let sdkman_dir = support::virtual_env(VirtualEnv::default());
+    let tmp = Path::new("tmp");
+    backdate(&support::write_file(
+        sdkman_dir.path(),
+        tmp,
+        "java-17.bin.part",
+        "x".to_string(),
+    ));
+    support::write_file(sdkman_dir.path(), tmp, "java-21.bin.part", "x".to_string());
+
+    env::set_var("SDKMAN_DIR", sdkman_dir.path().as_os_str());
+    Command::new(assert_cmd::cargo::cargo_bin!("flush"))
+        .arg("tmp")
+        .assert()
+        .success()
+        .stdout(contains(
+            "1 item(s) flushed from tmp, including 1 partial download(s).\n\
+             1 partial download(s) modified in the last hour kept, as an install may still be writing them.",
+        ))
+        .code(0);
+
+    let tmp_dir = sdkman_dir.path().join("tmp");
+    assert!(!tmp_dir.join("java-17.bin.part").exists());
+    assert!(tmp_dir.join("java-21.bin.part").is_file());
+
+    Ok(())
+}
+
+#[test]
+#[serial]
+fn should_flush_version_files() -> Result<(), Box<dyn std::error::Error>> {
+    let sdkman_dir = support::virtual_env(VirtualEnv {
+        cli_version: "5.0.0".to_string(),
+        native_version: "0.1.0".to_string(),
+        ..Default::default()
+    });
+
+    env::set_var("SDKMAN_DIR", sdkman_dir.path().as_os_str());
+    Command::new(assert_cmd::cargo::cargo_bin!("flush"))
+        .arg("version")
+        .assert()
+        .success()
+        .code(0);
+
+    assert!(!sdkman_dir.path().join("var/version").exists());
+    assert!(!sdkman_dir.path().join("var/version_native").exists());
+    assert!(sdkman_dir.path().join("var/candidates").exists());
+
+    Ok(())
+}
+
+#[test]
+#[serial]
+fn should_flush_all_targets_without_qualifier() -> Result<(), Box<dyn std::error::Error>> {
+    let sdkman_dir = support::virtual_env(VirtualEnv::default());
+    support::write_file(
+        sdkman_dir.path(),
+        Path::new("var/metadata"),
+        "java.headers",
+        "x".to_string(),
+    );
+    backdate(&support::write_file(
+        sdkman_dir.path(),
+        Path::new("tmp"),
+        "java-17.bin.part",
+        "x".to_string(),
+    ));
+
+    env::set_var("SDKMAN_DIR", sdkman_dir.path().as_os_str());
+    Command::new(assert_cmd::cargo::cargo_bin!("flush"))
+        .assert()
+        .success()
+        .stdout(contains("1 item(s) flushed from metadata."))
+        .code(0);
+
+    assert!(!sdkman_dir.path().join("var/metadata/java.headers").exists());
+    assert!(!sdkman_dir.path().join("tmp/java-17.bin.part").exists());
+    assert!(!sdkman_dir.path().join("var/version").exists());
+
+    Ok(())
+}
+
+#[test]
+#[serial]
+fn should_reject_unknown_flush_target() -> Result<(), Box<dyn std::error::Error>> {
+    let sdkman_dir = support::virtual_env(VirtualEnv::default());
+
+    env::set_var("SDKMAN_DIR", sdkman_dir.path().as_os_str());
+    Command::new(assert_cmd::cargo::cargo_bin!("flush"))
+        .arg("everything")
+        .assert()
+        .failure()
+        .stderr(contains("invalid value"));
+
+    Ok(())
+}
@@ -23,16 +23,20 @@ fn should_render_help_for_all_subcommands() -> Result<(), Box<dyn std::error::Er
         "current",
         "default",
         "env",
+        "exec",
         "flush",
         "home",
+        "init",
         "install",
         "list",
         "selfupdate",
+        "shim",
         "uninstall",
         "update",
         "upgrade",
         "use",
         "version",
+        "which",
     ];
 
     for arg in &args {
@@ -0,0 +1,359 @@
+#[cfg(test)]
+use assert_cmd::Command;
+use predicates::prelude::*;
+use predicates::str::contains;
+use serial_test::serial;
+use std::env;
+use std::fs;
+use std::path::Path;
+use support::{installed, Dialect, TestCandidate, VirtualEnv, DIALECTS};
+
+mod support;
+
+fn candidates_env() -> VirtualEnv {
+    VirtualEnv {
+        cli_version: "5.0.0".to_string(),
+        native_version: "0.1.0".to_string(),
+        candidates: vec![
+            TestCandidate {
+                name: "java",
+                versions: vec!["17.0.3-tem"],
+                current_version: "17.0.3-tem",
+            },
+            TestCandidate {
+                name: "maven",
+                versions: vec!["3.9.6"],
+                current_version: "3.9.6",
+            },
+        ],
+    }
+}
+
+#[test]
+#[serial]
+fn should_print_init_code_for_every_default() -> Result<(), Box<dyn std::error::Error>> {
+    let sdkman_dir = support::virtual_env(candidates_env());
+    let candidates = sdkman_dir.path().join("candidates");
+    fs::remove_file(candidates.join("maven/current"))?;
+    let path = format!("/usr/bin:{}", candidates.join("java/current/bin").display());
+
+    env::set_var("SDKMAN_DIR", sdkman_dir.path().as_os_str());
+    Command::new(assert_cmd::cargo::cargo_bin!("init"))
+        .arg("bash")
+        .env("PATH", &path)
+        .assert()
+        .success()
+        .stdout(contains(format!(
+            "export SDKMAN_DIR='{}'\nfor __sdkman_bin in '{}'; do case \":$PATH:\" in *\":$__sdkman_bin:\"*) ;; \
+            *) PATH=\"$__sdkman_bin:$PATH\" ;; esac; done; unset __sdkman_bin; export PATH\nexport JAVA_HOME='{}'\n\
+            unset SDKMAN_ENV\nsdk() {{\n",
+            sdkman_dir.path().display(),
+            candidates.join("java/current/bin").display(),
+            candidates.join("java/current").display()
+        )))
+        .stdout(contains("MAVEN_HOME").not())
+        .stdout(contains("--shell bash"))
+        .code(0);
+
+    Ok(())
+}
+
+#[test]
+#[serial]
+fn should_print_init_code_for_detected_shell() -> Result<(), Box<dyn std::error::Error>> {
+    let sdkman_dir = support::virtual_env(candidates_env());
+    let candidates = sdkman_dir.path().join("candidates");
+
+    env::set_var("SDKMAN_DIR", sdkman_dir.path().as_os_str());
+    Command::new(assert_cmd::cargo::cargo_bin!("init"))
+        .env("PATH", "/usr/bin")
+        .env("SHELL", "/usr/bin/fish")
+        .assert()
+        .success()
+        .stdout(contains(format!(
+            "for __sdkman_bin in '{}' '{}'; contains -- $__sdkman_bin $PATH; \
+            or set -gx PATH $__sdkman_bin $PATH; end\n",
+            candidates.join("java/current/bin").display(),
+            candidates.join("maven/current/bin").display()
+        )))
+        .stdout(contains(format!(
+            "set -gx MAVEN_HOME '{}'\n",
+            candidates.join("maven/current").display()
+        )))
+        .stdout(contains("function sdk\n"))
+        .code(0);
+
+    Ok(())
+}
+
+// Runs the init code of the dialect followed by `commands` in `sdkman_dir`, with the native `env`
+// and `use` in libexec.
+fn eval_init(
+    sdkman_dir: &Path,
+    dialect: &Dialect,
+    commands: &str,
+) -> Result<std::process::Output, Box<dyn std::error::Error>> {
+    let libexec = sdkman_dir.join("libexec");
+    fs::create_dir_all(&libexec)?;
+    for (name, binary) in [
+        ("env", assert_cmd::cargo::cargo_bin!("env")),
+        ("use", assert_cmd::cargo::cargo_bin!("use")),
+    ] {
+        fs::copy(
+            binary,
+            libexec.join(format!("{}{}", name, env::consts::EXE_SUFFIX)),
+        )?;
+    }
+
+    env::set_var("SDKMAN_DIR", sdkman_dir.as_os_str());
+    let init = Command::new(assert_cmd::cargo::cargo_bin!("init"))
+        .arg(dialect.shell)
+        .output()?;
+    let script = sdkman_dir.join(format!("init.{}", dialect.extension));
+    fs::write(
+        &script,
+        format!("{}\n{}\n", String::from_utf8(init.stdout)?, commands),
+    )?;
+
+    Ok(std::process::Command::new(dialect.shell)
+        .args(dialect.arguments)
+        .arg(&script)
+        .current_dir(sdkman_dir)
+        .output()?)
+}
+
+#[test]
+#[serial]
+fn should_define_sdk_function_that_every_installed_shell_can_use(
+) -> Result<(), Box<dyn std::error::Error>> {
+    for dialect in DIALECTS.iter().filter(|d| installed(d.shell)) {
+        let sdkman_dir = support::virtual_env(candidates_env());
+
+        let commands = format!("sdk u java 17.0.3-tem\n{}", dialect.print_java_home);
+        let output = eval_init(sdkman_dir.path(), dialect, &commands)?;
+
+        assert_eq!(
+            String::from_utf8_lossy(&output.stdout),
+            sdkman_dir
+                .path()
+                .join("candidates/java/17.0.3-tem")
+                .display()
+                .to_string(),
+            "{}: {}",
+            dialect.shell,
+            String::from_utf8_lossy(&output.stderr)
+        );
+    }
+
+    Ok(())
+}
+
+#[test]
+#[serial]
+fn should_not_duplicate_path_entries_when_evaluated_again() -> Result<(), Box<dyn std::error::Error>>
+{
+    for dialect in DIALECTS.iter().filter(|d| installed(d.shell)) {
+        let sdkman_dir = support::virtual_env(candidates_env());
+        let candidates = sdkman_dir.path().join("candidates");
+
+        env::set_var("SDKMAN_DIR", sdkman_dir.path().as_os_str());
+        let init = Command::new(assert_cmd::cargo::cargo_bin!("init"))
+            .arg(dialect.shell)
+            .output()?;
+        let commands = format!(
+            "{}\n{}",
+            String::from_utf8(init.stdout)?,
+            dialect.print_path
+        );
+        let output = eval_init(sdkman_dir.path(), dialect, &commands)?;
+
+        let path = String::from_utf8_lossy(&output.stdout)
+            .trim_end()
+            .to_string();
+        let bin_dirs = [
+            candidates.join("maven/current/bin").display().to_string(),
+            candidates.join("java/current/bin").display().to_string(),
+        ];
+        assert!(
+            path.starts_with(&format!("{}:{}:", bin_dirs[0], bin_dirs[1])),
+            "{}: {} {}",
+            dialect.shell,
+            path,
+            String::from_utf8_lossy(&output.stderr)
+        );
+        for bin_dir in &bin_dirs {
+            assert_eq!(
+                path.split(':').filter(|e| e == bin_dir).count(),
+                1,
+                "{}: {}",
+                dialect.shell,
+                path
+            );
+        }
+    }
+
+    Ok(())
+}
+
+#[test]
+#[serial]
+fn should_print_env_check_without_evaluating_it() -> Result<(), Box<dyn std::error::Error>> {
+    for dialect in DIALECTS.iter().filter(|d| installed(d.shell)) {
+        let sdkman_dir = support::virtual_env(candidates_env());
+        support::write_file(
+            sdkman_dir.path(),
+            Path::new(""),
+            ".sdkmanrc",
+            "java=17.0.3-tem\n".to_string(),
+        );
+
+        let output = eval_init(sdkman_dir.path(), dialect, "sdk env check")?;
+
+        assert!(
+            String::from_utf8_lossy(&output.stdout).starts_with("CANDIDATE  EXPECTED"),
+            "{}: {}",
+            dialect.shell,
+            String::from_utf8_lossy(&output.stderr)
+        );
+        assert!(!String::from_utf8_lossy(&output.stderr).contains("command not found"));
+    }
+
+    Ok(())
+}
+
+#[test]
+#[serial]
+fn should_switch_versions_on_directory_change_with_auto_env(
+) -> Result<(), Box<dyn std::error::Error>> {
+    for dialect in DIALECTS.iter().filter(|d| installed(d.shell)) {
+        let sdkman_dir = support::virtual_env(candidates_env());
+        support::write_file(
+            sdkman_dir.path(),
+            Path::new("etc"),
+            "config",
+            "sdkman_auto_env=true\n".to_string(),
+        );
+        support::write_file(
+            sdkman_dir.path(),
+            Path::new("project"),
+            ".sdkmanrc",
+            "java=17.0.3-tem\n".to_string(),
+        );
+
+        let commands = format!("{}\n{}", dialect.enter_project, dialect.print_java_home);
+        let output = eval_init(sdkman_dir.path(), dialect, &commands)?;
+
+        assert_eq!(
+            String::from_utf8_lossy(&output.stdout),
+            sdkman_dir
+                .path()
+                .join("candidates/java/17.0.3-tem")
+                .display()
+                .to_string(),
+            "{}: {}",
+            dialect.shell,
+            String::from_utf8_lossy(&output.stderr)
+        );
+    }
+
+    Ok(())
+}
+
+// A shell started inside an activated project, such as a new tmux pane, inherits SDKMAN_ENV
+// from its parent but gets the default versions from init.
+#[test]
+#[serial]
+fn should_activate_project_again_in_nested_shell_with_auto_env(
+) -> Result<(), Box<dyn std::error::Error>> {
+    for dialect in DIALECTS.iter().filter(|d| installed(d.shell)) {
+        let sdkman_dir = support::virtual_env(candidates_env());
+        support::write_file(
+            sdkman_dir.path(),
+            Path::new("etc"),
+            "config",
+            "sdkman_auto_env=true\n".to_string(),
+        );
+        let sdkmanrc = support::write_file(
+            sdkman_dir.path(),
+            Path::new("project"),
+            ".sdkmanrc",
+            "java=17.0.3-tem\n".to_string(),
+        );
+
+        env::set_var("SDKMAN_ENV", &sdkmanrc);
+        let commands = format!("{}\n{}", dialect.enter_project, dialect.print_java_home);
+        let output = eval_init(sdkman_dir.path(), dialect, &commands);
+        env::remove_var("SDKMAN_ENV");
+        let output = output?;
+
+        assert_eq!(
+            String::from_utf8_lossy(&output.stdout),
+            sdkman_dir
+                .path()
+                .join("candidates/java/17.0.3-tem")
+                .display()
+                .to_string(),
+            "{}: {}",
+            dialect.shell,
+            String::from_utf8_lossy(&output.stderr)
+        );
+    }
+
+    Ok(())
+}
+
+#[test]
+#[serial]
+fn should_hand_other_subcommands_to_the_bash_implementation(
+) -> Result<(), Box<dyn std::error::Error>> {
+    for dialect in DIALECTS.iter().filter(|d| installed(d.shell)) {
+        let sdkman_dir = support::virtual_env(candidates_env());
+        support::write_file(
+            sdkman_dir.path(),
+            Path::new("bin"),
+            "sdkman-init.sh",
+            "sdk() { echo \"bash implementation: $*\"; SDKMAN_OFFLINE_MODE=true; }\n".to_string(),
+        );
+
+        let output = eval_init(sdkman_dir.path(), dialect, "sdk offline enable")?;
+
+        assert_eq!(
+            String::from_utf8_lossy(&output.stdout),
+            "bash implementation: offline enable\n",
+            "{}: {}",
+            dialect.shell,
+            String::from_utf8_lossy(&output.stderr)
+        );
+    }
+
+    Ok(())
+}
+
+#[test]
+#[serial]
+fn should_keep_state_of_the_bash_implementation_in_posix_shells(
+) -> Result<(), Box<dyn std::error::Error>> {
+    for dialect in DIALECTS.iter().take(2).filter(|d| installed(d.shell)) {
+        let sdkman_dir = support::virtual_env(candidates_env());
+        support::write_file(
+            sdkman_dir.path(),
+            Path::new("bin"),
+            "sdkman-init.sh",
+            "sdk() { SDKMAN_OFFLINE_MODE=true; }\n".to_string(),
+        );
+
+        let commands =
+            r#"sdk offline enable && sdk u java 17.0.3-tem && printf '%s' "$SDKMAN_OFFLINE_MODE""#;
+        let output = eval_init(sdkman_dir.path(), dialect, commands)?;
+
+        assert_eq!(
+            String::from_utf8_lossy(&output.stdout),
+            "true",
+            "{}: {}",
+            dialect.shell,
+            String::from_utf8_lossy(&output.stderr)
+        );
+    }
+
+    Ok(())
+}
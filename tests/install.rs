@@ -0,0 +1,289 @@
+#[cfg(test)]
+use assert_cmd::Command;
+use flate2::write::GzEncoder;
+use flate2::Compression;
+use predicates::str::contains;
+use sdkman_cli_native::checksum::{compute, Algorithm};
+use serial_test::serial;
+use std::path::Path;
+use std::{env, fs, thread};
+use support::{TestCandidate, VirtualEnv};
+use tiny_http::{Header, Response, Server};
+
+mod support;
+
+fn scala_env() -> VirtualEnv {
+    VirtualEnv {
+        cli_version: "0.0.1".to_string(),
+        native_version: "0.0.1".to_string(),
+        candidates: vec![TestCandidate {
+            name: "scala",
+            versions: vec!["0.0.1"],
+            current_version: "0.0.1",
+        }],
+    }
+}
+
+fn archive_fixture(dir: &Path) -> Vec<u8> {
+    let launcher = "#!/bin/bash\necho Running scala 0.0.2\n";
+    let mut header = tar::Header::new_gnu();
+    header.set_size(launcher.len() as u64);
+    header.set_mode(0o755);
+    header.set_cksum();
+    let mut builder = tar::Builder::new(GzEncoder::new(Vec::new(), Compression::fast()));
+    builder
+        .append_data(&mut header, "scala-0.0.2/bin/scala", launcher.as_bytes())
+        .unwrap();
+    let bytes = builder.into_inner().unwrap().finish().unwrap();
+    fs::write(dir.join("fixture.tar.gz"), &bytes).unwrap();
+    bytes
+}
+
+// Sidecar checksum files are never published, so the only checksum is the optional header.
+fn serve_archive(archive: Vec<u8>, sha256: Option<String>) -> String {
+    let server = Server::http("127.0.0.1:0").unwrap();
+    let url = format!("http://{}", server.server_addr().to_ip().unwrap());
+    thread::spawn(move || {
+        for request in server.incoming_requests() {
+            if [".sha512", ".sha256", ".md5"]
+                .iter()
+                .any(|extension| request.url().ends_with(extension))
+            {
+                request.respond(Response::empty(404)).unwrap();
+                continue;
+            }
+            let mut response = Response::from_data(archive.clone())
+                .with_header(Header::from_bytes("X-Sdkman-ArchiveType", "tar").unwrap());
+            if let Some(sha256) = &sha256 {
+                response.add_header(
+                    Header::from_bytes("X-Sdkman-Checksum-SHA-256", sha256.as_str()).unwrap(),
+                );
+            }
+            request.respond(response).unwrap();
+        }
+    });
+    url
+}
+
+#[test]
+#[serial]
+fn should_download_and_install_a_remote_version() -> Result<(), Box<dyn std::error::Error>> {
+    let sdkman_dir = support::virtual_env(scala_env());
+    let archive = archive_fixture(sdkman_dir.path());
+    let sha256 = compute(&sdkman_dir.path().join("fixture.tar.gz"), Algorithm::Sha256)?;
+    let url = serve_archive(archive, Some(sha256));
+
+    env::set_var("SDKMAN_DIR", sdkman_dir.path().as_os_str());
+    env::set_var("SDKMAN_CANDIDATES_API", &url);
+    Command::new(assert_cmd::cargo::cargo_bin!("install"))
+        .arg("scala")
+        .arg("0.0.2")
+        .assert()
+        .success()
+        .stdout(contains("installed scala 0.0.2."))
+        .code(0);
+    env::remove_var("SDKMAN_CANDIDATES_API");
+
+    let file = sdkman_dir.path().join("candidates/scala/0.0.2/bin/scala");
+    let content = fs::read_to_string(file).unwrap();
+    assert!(content.contains("Running scala 0.0.2"));
+    assert!(!sdkman_dir.path().join("tmp/scala-0.0.2.tar.gz").exists());
+
+    Ok(())
+}
+
+#[test]
+#[serial]
+fn should_quarantine_archive_with_checksum_mismatch() -> Result<(), Box<dyn std::error::Error>> {
+    let sdkman_dir = support::virtual_env(scala_env());
+    let archive = archive_fixture(sdkman_dir.path());
+    let url = serve_archive(archive, Some("0".repeat(64)));
+
+    env::set_var("SDKMAN_DIR", sdkman_dir.path().as_os_str());
+    env::set_var("SDKMAN_CANDIDATES_API", &url);
+    Command::new(assert_cmd::cargo::cargo_bin!("install"))
+        .arg("scala")
+        .arg("0.0.2")
+        .assert()
+        .failure()
+        .stderr(contains("SHA-256 checksum mismatch"))
+        .code(65);
+    env::remove_var("SDKMAN_CANDIDATES_API");
+
+    assert!(!sdkman_dir.path().join("candidates/scala/0.0.2").exists());
+    assert!(sdkman_dir
+        .path()
+        .join("tmp/quarantine/scala-0.0.2.tar.gz")
+        .is_file());
+
+    Ok(())
+}
+
+#[test]
+#[serial]
+fn should_refuse_archive_without_checksum() -> Result<(), Box<dyn std::error::Error>> {
+    let sdkman_dir = support::virtual_env(scala_env());
+    let archive = archive_fixture(sdkman_dir.path());
+    let url = serve_archive(archive, None);
+
+    env::set_var("SDKMAN_DIR", sdkman_dir.path().as_os_str());
+    env::set_var("SDKMAN_CANDIDATES_API", &url);
+    Command::new(assert_cmd::cargo::cargo_bin!("install"))
+        .arg("scala")
+        .arg("0.0.2")
+        .assert()
+        .failure()
+        .stderr(contains("no checksum available"))
+        .code(65);
+    env::remove_var("SDKMAN_CANDIDATES_API");
+
+    assert!(!sdkman_dir.path().join("candidates/scala/0.0.2").exists());
+
+    Ok(())
+}
+
+#[test]
+#[serial]
+fn should_skip_checksum_verification_when_disabled() -> Result<(), Box<dyn std::error::Error>> {
+    let sdkman_dir = support::virtual_env(scala_env());
+    support::write_file(
+        sdkman_dir.path(),
+        Path::new("etc"),
+        "config",
+        "sdkman_checksum_enable=false\n".to_string(),
+    );
+    let archive = archive_fixture(sdkman_dir.path());
+    let url = serve_archive(archive, Some("0".repeat(64)));
+
+    env::set_var("SDKMAN_DIR", sdkman_dir.path().as_os_str());
+    env::set_var("SDKMAN_CANDIDATES_API", &url);
+    Command::new(assert_cmd::cargo::cargo_bin!("install"))
+        .arg("scala")
+        .arg("0.0.2")
+        .assert()
+        .success()
+        .code(0);
+    env::remove_var("SDKMAN_CANDIDATES_API");
+
+    assert!(sdkman_dir
+        .path()
+        .join("candidates/scala/0.0.2/bin/scala")
+        .is_file());
+
+    Ok(())
+}
+
+#[test]
+#[serial]
+fn should_install_a_local_version() -> Result<(), Box<dyn std::error::Error>> {
+    let sdkman_dir = support::virtual_env(scala_env());
+    let local_dir = support::prepare_sdkman_dir();
+    support::write_file(
+        local_dir.path(),
+        Path::new("bin"),
+        "scala",
+        "echo Running scala local".to_string(),
+    );
+
+    env::set_var("SDKMAN_DIR", sdkman_dir.path().as_os_str());
+    Command::new(assert_cmd::cargo::cargo_bin!("install"))
+        .arg("scala")
+        .arg("local")
+        .arg(local_dir.path())
+        .assert()
+        .success()
+        .stdout(contains("linked scala local to"))
+        .code(0);
+
+    let file = sdkman_dir
+        .path()
+        .join("candidates")
+        .join("scala")
+        .join("local")
+        .join("bin")
+        .join("scala");
+    let content = fs::read_to_string(file).unwrap();
+    assert!(content.contains("Running scala local"));
+
+    Ok(())
+}
+
+#[test]
+#[serial]
+fn should_fail_if_version_is_already_installed() -> Result<(), Box<dyn std::error::Error>> {
+    let sdkman_dir = support::virtual_env(scala_env());
+    let local_dir = support::prepare_sdkman_dir();
+    fs::create_dir_all(local_dir.path().join("bin"))?;
+
+    env::set_var("SDKMAN_DIR", sdkman_dir.path().as_os_str());
+    Command::new(assert_cmd::cargo::cargo_bin!("install"))
+        .arg("scala")
+        .arg("0.0.1")
+        .arg(local_dir.path())
+        .assert()
+        .failure()
+        .stderr(contains("scala 0.0.1 is already installed."))
+        .code(1);
+
+    Ok(())
+}
+
+#[test]
+#[serial]
+fn should_fail_if_local_path_does_not_exist() -> Result<(), Box<dyn std::error::Error>> {
+    let sdkman_dir = support::virtual_env(scala_env());
+    let missing_path = sdkman_dir.path().join("missing");
+
+    env::set_var("SDKMAN_DIR", sdkman_dir.path().as_os_str());
+    Command::new(assert_cmd::cargo::cargo_bin!("install"))
+        .arg("scala")
+        .arg("local")
+        .arg(&missing_path)
+        .assert()
+        .failure()
+        .stderr(contains("is not a directory"))
+        .code(1);
+
+    assert!(!sdkman_dir.path().join("candidates/scala/local").exists());
+
+    Ok(())
+}
+
+#[test]
+#[serial]
+fn should_fail_if_local_path_has_no_bin_folder() -> Result<(), Box<dyn std::error::Error>> {
+    let sdkman_dir = support::virtual_env(scala_env());
+    let local_dir = support::prepare_sdkman_dir();
+
+    env::set_var("SDKMAN_DIR", sdkman_dir.path().as_os_str());
+    Command::new(assert_cmd::cargo::cargo_bin!("install"))
+        .arg("scala")
+        .arg("local")
+        .arg(local_dir.path())
+        .assert()
+        .failure()
+        .stderr(contains("does not contain a bin folder"))
+        .code(1);
+
+    Ok(())
+}
+
+#[test]
+#[serial]
+fn should_fail_if_candidate_is_invalid() -> Result<(), Box<dyn std::error::Error>> {
+    let sdkman_dir = support::virtual_env(scala_env());
+    let local_dir = support::prepare_sdkman_dir();
+    fs::create_dir_all(local_dir.path().join("bin"))?;
+
+    env::set_var("SDKMAN_DIR", sdkman_dir.path().as_os_str());
+    Command::new(assert_cmd::cargo::cargo_bin!("install"))
+        .arg("zcala")
+        .arg("local")
+        .arg(local_dir.path())
+        .assert()
+        .failure()
+        .stderr(contains("zcala is not a valid candidate"))
+        .code(1);
+
+    Ok(())
+}
@@ -0,0 +1,113 @@
+#[cfg(test)]
+use assert_cmd::Command;
+use predicates::prelude::*;
+use predicates::str::contains;
+use serial_test::serial;
+use std::env;
+use std::path::Path;
+use support::{TestCandidate, VirtualEnv};
+use symlink::symlink_dir;
+
+mod support;
+
+fn installed_env() -> VirtualEnv {
+    VirtualEnv {
+        cli_version: "5.0.0".to_string(),
+        native_version: "0.1.0".to_string(),
+        candidates: vec![
+            TestCandidate {
+                name: "java",
+                versions: vec!["8.0.392-amzn", "11.0.15-tem", "17.0.3-tem"],
+                current_version: "11.0.15-tem",
+            },
+            TestCandidate {
+                name: "maven",
+                versions: vec!["3.9.6"],
+                current_version: "3.9.6",
+            },
+        ],
+    }
+}
+
+#[test]
+#[serial]
+fn should_list_installed_versions_with_markers() -> Result<(), Box<dyn std::error::Error>> {
+    let sdkman_dir = support::virtual_env(installed_env());
+    let java = sdkman_dir.path().join("candidates/java");
+    let local_jdk = support::prepare_sdkman_dir();
+    symlink_dir(local_jdk.path(), java.join("21-local"))?;
+
+    env::set_var("SDKMAN_DIR", sdkman_dir.path().as_os_str());
+    Command::new(assert_cmd::cargo::cargo_bin!("list"))
+        .args(["java", "--installed"])
+        .env("PATH", format!("{}:/usr/bin", java.join("17.0.3-tem/bin").display()))
+        .env("NO_COLOR", "1")
+        .assert()
+        .success()
+        .stdout(contains(
+            "Installed java versions:\n\n   + 21-local\n > * 17.0.3-tem\n > * 11.0.15-tem (default)\n   * 8.0.392-amzn\n",
+        ))
+        .stdout(contains("> - currently in use"))
+        .code(0);
+
+    Ok(())
+}
+
+#[test]
+#[serial]
+fn should_list_every_installed_candidate() -> Result<(), Box<dyn std::error::Error>> {
+    let sdkman_dir = support::virtual_env(installed_env());
+    let maven = sdkman_dir.path().join("candidates/maven");
+    support::write_file(
+        sdkman_dir.path(),
+        Path::new("var"),
+        "candidates",
+        "java,maven,gradle".to_string(),
+    );
+
+    env::set_var("SDKMAN_DIR", sdkman_dir.path().as_os_str());
+    Command::new(assert_cmd::cargo::cargo_bin!("list"))
+        .arg("--installed")
+        .env(
+            "PATH",
+            format!("{}:/usr/bin", maven.join("current/bin").display()),
+        )
+        .env("NO_COLOR", "1")
+        .assert()
+        .success()
+        .stdout(contains("java\n   * 17.0.3-tem\n"))
+        .stdout(contains("\n\nmaven\n > * 3.9.6 (default)\n"))
+        .stdout(contains("gradle").not())
+        .code(0);
+
+    Ok(())
+}
+
+#[test]
+#[serial]
+fn should_hand_online_list_to_the_bash_implementation() -> Result<(), Box<dyn std::error::Error>> {
+    let sdkman_dir = support::virtual_env(installed_env());
+
+    env::set_var("SDKMAN_DIR", sdkman_dir.path().as_os_str());
+    Command::new(assert_cmd::cargo::cargo_bin!("list"))
+        .arg("java")
+        .assert()
+        .failure()
+        .stderr(contains("The native list only shows installed versions"))
+        .code(1);
+
+    support::write_file(
+        sdkman_dir.path(),
+        Path::new("bin"),
+        "sdkman-init.sh",
+        "__sdk_list() { echo \"bash implementation: $*\"; return 3; }\n".to_string(),
+    );
+    Command::new(assert_cmd::cargo::cargo_bin!("list"))
+        .arg("java")
+        .assert()
+        .failure()
+        .stdout("bash implementation: java\n")
+        .code(3);
+
+    Ok(())
+}
@@ -0,0 +1,196 @@
+#![cfg(unix)]
+
+#[cfg(test)]
+use assert_cmd::Command;
+use predicates::str::contains;
+use serial_test::serial;
+use std::env;
+use std::fs;
+use std::os::unix::fs::PermissionsExt;
+use std::path::Path;
+use support::{TestCandidate, VirtualEnv};
+
+mod support;
+
+fn java_env() -> VirtualEnv {
+    VirtualEnv {
+        cli_version: "5.0.0".to_string(),
+        native_version: "0.1.0".to_string(),
+        candidates: vec![TestCandidate {
+            name: "java",
+            versions: vec!["11.0.15-tem", "17.0.3-tem"],
+            current_version: "11.0.15-tem",
+        }],
+    }
+}
+
+fn make_executable(path: &Path) {
+    fs::set_permissions(path, fs::Permissions::from_mode(0o755)).expect("cannot chmod");
+}
+
+#[test]
+#[serial]
+fn should_run_version_selected_for_the_directory() -> Result<(), Box<dyn std::error::Error>> {
+    let sdkman_dir = support::virtual_env(java_env());
+    let candidates = sdkman_dir.path().join("candidates");
+    make_executable(&candidates.join("java/11.0.15-tem/bin/java"));
+    make_executable(&candidates.join("java/17.0.3-tem/bin/java"));
+    let project_dir = support::prepare_sdkman_dir();
+    support::write_file(
+        project_dir.path(),
+        Path::new(""),
+        ".sdkmanrc",
+        "java=17.0.3-tem\n".to_string(),
+    );
+    let outside_dir = support::prepare_sdkman_dir();
+
+    env::set_var("SDKMAN_DIR", sdkman_dir.path().as_os_str());
+    Command::new(assert_cmd::cargo::cargo_bin!("shim"))
+        .arg("rehash")
+        .assert()
+        .success()
+        .stdout(contains("1 shims created and 0 removed"))
+        .code(0);
+
+    let shim = sdkman_dir.path().join("shims/java");
+    Command::new(&shim)
+        .current_dir(project_dir.path())
+        .env_remove("SDKMAN_JAVA_VERSION")
+        .assert()
+        .success()
+        .stdout("Running java 17.0.3-tem\n")
+        .code(0);
+
+    Command::new(&shim)
+        .current_dir(outside_dir.path())
+        .env_remove("SDKMAN_JAVA_VERSION")
+        .assert()
+        .success()
+        .stdout("Running java 11.0.15-tem\n")
+        .code(0);
+
+    Command::new(&shim)
+        .current_dir(project_dir.path())
+        .env("SDKMAN_JAVA_VERSION", "11.0.15-tem")
+        .assert()
+        .success()
+        .stdout("Running java 11.0.15-tem\n")
+        .code(0);
+
+    Ok(())
+}
+
+#[test]
+#[serial]
+fn should_fail_for_version_not_installed() -> Result<(), Box<dyn std::error::Error>> {
+    let sdkman_dir = support::virtual_env(java_env());
+    let candidates = sdkman_dir.path().join("candidates");
+    make_executable(&candidates.join("java/17.0.3-tem/bin/java"));
+
+    env::set_var("SDKMAN_DIR", sdkman_dir.path().as_os_str());
+    Command::new(assert_cmd::cargo::cargo_bin!("shim"))
+        .arg("rehash")
+        .assert()
+        .success();
+
+    Command::new(sdkman_dir.path().join("shims/java"))
+        .env("SDKMAN_JAVA_VERSION", "21.0.2-tem")
+        .assert()
+        .failure()
+        .stderr(contains(
+            "java 21.0.2-tem selected by $SDKMAN_JAVA_VERSION is not installed",
+        ))
+        .code(127);
+
+    fs::remove_dir_all(candidates.join("java/17.0.3-tem"))?;
+    Command::new(assert_cmd::cargo::cargo_bin!("shim"))
+        .arg("rehash")
+        .assert()
+        .success()
+        .stdout(contains("0 shims created and 1 removed"));
+    assert!(!sdkman_dir.path().join("shims/java").exists());
+
+    Ok(())
+}
+
+#[test]
+#[serial]
+fn should_run_version_used_in_the_shell_with_its_bin_dir_first(
+) -> Result<(), Box<dyn std::error::Error>> {
+    let sdkman_dir = support::virtual_env(java_env());
+    let candidates = sdkman_dir.path().join("candidates");
+    make_executable(&candidates.join("java/11.0.15-tem/bin/java"));
+    let java_17 = support::write_file(
+        &candidates,
+        Path::new("java/17.0.3-tem/bin"),
+        "java",
+        "#!/bin/sh\necho \"$PATH\"\n".to_string(),
+    );
+    make_executable(&java_17);
+    let shims_dir = sdkman_dir.path().join("shims");
+
+    env::set_var("SDKMAN_DIR", sdkman_dir.path().as_os_str());
+    Command::new(assert_cmd::cargo::cargo_bin!("shim"))
+        .arg("rehash")
+        .assert()
+        .success();
+
+    Command::new(shims_dir.join("java"))
+        .env_remove("SDKMAN_JAVA_VERSION")
+        .env("JAVA_HOME", candidates.join("java/17.0.3-tem"))
+        .env(
+            "PATH",
+            format!(
+                "{}:/usr/bin:{}",
+                shims_dir.display(),
+                candidates.join("java/current/bin").display()
+            ),
+        )
+        .assert()
+        .success()
+        .stdout(format!(
+            "{}:{}:/usr/bin\n",
+            candidates.join("java/17.0.3-tem/bin").display(),
+            shims_dir.display()
+        ))
+        .code(0);
+
+    Command::new(shims_dir.join("java"))
+        .env_remove("SDKMAN_JAVA_VERSION")
+        .env("JAVA_HOME", candidates.join("java/current"))
+        .assert()
+        .success()
+        .stdout("Running java 11.0.15-tem\n")
+        .code(0);
+
+    Ok(())
+}
+
+#[test]
+#[serial]
+fn should_run_executable_with_dots_in_its_name() -> Result<(), Box<dyn std::error::Error>> {
+    let sdkman_dir = support::virtual_env(java_env());
+    let runant = support::write_file(
+        &sdkman_dir.path().join("candidates"),
+        Path::new("java/11.0.15-tem/bin"),
+        "runant.py",
+        "#!/bin/sh\necho Running runant.py\n".to_string(),
+    );
+    make_executable(&runant);
+
+    env::set_var("SDKMAN_DIR", sdkman_dir.path().as_os_str());
+    Command::new(assert_cmd::cargo::cargo_bin!("shim"))
+        .arg("rehash")
+        .assert()
+        .success()
+        .stdout(contains("1 shims created and 0 removed"));
+
+    Command::new(sdkman_dir.path().join("shims/runant.py"))
+        .env_remove("SDKMAN_JAVA_VERSION")
+        .assert()
+        .success()
+        .stdout("Running runant.py\n")
+        .code(0);
+
+    Ok(())
+}
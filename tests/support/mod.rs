@@ -83,7 +83,7 @@ echo Running {} {}
             .expect("cannot create current symlink");
     }
 
-    return sdkman_dir;
+    sdkman_dir
 }
 
 pub fn prepare_sdkman_dir() -> TempDir {
@@ -100,11 +100,79 @@ pub fn write_file(
     content: String,
 ) -> PathBuf {
     let absolute_path = temp_dir.join(relative_path);
-    create_dir_all(absolute_path.to_owned()).expect("could not create nested dirs");
+    create_dir_all(&absolute_path).expect("could not create nested dirs");
 
     let file_path = absolute_path.join(file_name);
     let mut file = File::create(&file_path).expect("could not create file");
-    write!(file, "{}", content.to_string()).expect("could not write to file");
+    write!(file, "{}", content).expect("could not write to file");
 
     file_path
 }
+
+/// How a test runs a script file in one of the shells that the native subcommands print code
+/// for, and the code that inspects the environment of that shell. The code that prints the
+/// `PATH` joins it with colons and ends the line; the code that prints `JAVA_HOME` does not.
+// Not every test file evaluates shell code, so the dialects go unused in some of them.
+#[allow(dead_code)]
+pub struct Dialect {
+    pub shell: &'static str,
+    pub arguments: &'static [&'static str],
+    pub extension: &'static str,
+    pub enter_project: &'static str,
+    pub print_path: &'static str,
+    pub print_java_home: &'static str,
+}
+
+#[allow(dead_code)]
+pub const DIALECTS: [Dialect; 5] = [
+    Dialect {
+        shell: "bash",
+        arguments: &[],
+        extension: "sh",
+        enter_project: r#"cd project && eval "$PROMPT_COMMAND""#,
+        print_path: r#"printf '%s\n' "$PATH""#,
+        print_java_home: r#"printf '%s' "$JAVA_HOME""#,
+    },
+    Dialect {
+        shell: "zsh",
+        arguments: &["-f"],
+        extension: "zsh",
+        enter_project: "cd project",
+        print_path: r#"printf '%s\n' "$PATH""#,
+        print_java_home: r#"printf '%s' "$JAVA_HOME""#,
+    },
+    Dialect {
+        shell: "fish",
+        arguments: &[],
+        extension: "fish",
+        enter_project: "cd project",
+        print_path: "printf '%s\\n' (string join : $PATH)",
+        print_java_home: r#"printf '%s' "$JAVA_HOME""#,
+    },
+    Dialect {
+        shell: "pwsh",
+        arguments: &["-NoProfile", "-File"],
+        extension: "ps1",
+        enter_project: "Set-Location project; prompt | Out-Null",
+        print_path: "[Console]::Write($env:PATH + \"`n\")",
+        print_java_home: "[Console]::Write($env:JAVA_HOME)",
+    },
+    Dialect {
+        shell: "nu",
+        arguments: &["-n"],
+        extension: "nu",
+        enter_project:
+            "cd project\ndo --env ($env.config.hooks.env_change.PWD | last) null $env.PWD",
+        print_path: "print ($env.PATH | str join ':')",
+        print_java_home: "print -n $env.JAVA_HOME",
+    },
+];
+
+// Shells other than bash are optional, so their evaluation is skipped where they are missing.
+#[allow(dead_code)]
+pub fn installed(shell: &str) -> bool {
+    std::process::Command::new(shell)
+        .arg("--version")
+        .output()
+        .is_ok_and(|output| output.status.success())
+}
@@ -0,0 +1,159 @@
+#[cfg(test)]
+use assert_cmd::Command;
+use predicates::str::contains;
+use serial_test::serial;
+use std::env;
+#[cfg(unix)]
+use support::{installed, DIALECTS};
+use support::{TestCandidate, VirtualEnv};
+
+mod support;
+
+fn java_env() -> VirtualEnv {
+    VirtualEnv {
+        cli_version: "5.0.0".to_string(),
+        native_version: "0.1.0".to_string(),
+        candidates: vec![TestCandidate {
+            name: "java",
+            versions: vec!["11.0.15-tem", "17.0.3-tem"],
+            current_version: "11.0.15-tem",
+        }],
+    }
+}
+
+#[test]
+#[serial]
+fn should_rewrite_path_without_duplicates() -> Result<(), Box<dyn std::error::Error>> {
+    let sdkman_dir = support::virtual_env(java_env());
+    let java = sdkman_dir.path().join("candidates/java");
+    let path = format!(
+        "/usr/bin:{}:/bin:{}:{}",
+        java.join("11.0.15-tem/bin").display(),
+        java.join("current/bin").display(),
+        java.join("17.0.3-tem/bin").display()
+    );
+
+    env::set_var("SDKMAN_DIR", sdkman_dir.path().as_os_str());
+    Command::new(assert_cmd::cargo::cargo_bin!("use"))
+        .args(["java", "17.0.3-tem"])
+        .env("PATH", &path)
+        .env("SHELL", "/bin/bash")
+        .assert()
+        .success()
+        .stdout(format!(
+            "export PATH='/usr/bin:{}:/bin'\nexport JAVA_HOME='{}'\n",
+            java.join("17.0.3-tem/bin").display(),
+            java.join("17.0.3-tem").display()
+        ))
+        .stderr(contains("Using java version 17.0.3-tem in this shell."))
+        .code(0);
+
+    Ok(())
+}
+
+#[test]
+#[serial]
+fn should_print_exports_for_requested_shell() -> Result<(), Box<dyn std::error::Error>> {
+    let sdkman_dir = support::virtual_env(java_env());
+    let java = sdkman_dir.path().join("candidates/java");
+
+    env::set_var("SDKMAN_DIR", sdkman_dir.path().as_os_str());
+    Command::new(assert_cmd::cargo::cargo_bin!("use"))
+        .args(["java", "17.0.3-tem", "--shell", "nu"])
+        .env("PATH", "/usr/bin:/bin")
+        .assert()
+        .success()
+        .stdout(format!(
+            "$env.PATH = [\"{}\", \"/usr/bin\", \"/bin\"]\n$env.JAVA_HOME = \"{}\"\n",
+            java.join("17.0.3-tem/bin").display(),
+            java.join("17.0.3-tem").display()
+        ))
+        .code(0);
+
+    Command::new(assert_cmd::cargo::cargo_bin!("use"))
+        .args(["java", "17.0.3-tem"])
+        .env("PATH", "/usr/bin")
+        .env("SHELL", "/usr/local/bin/fish")
+        .assert()
+        .success()
+        .stdout(format!(
+            "set -gx PATH '{}' '/usr/bin'\nset -gx JAVA_HOME '{}'\n",
+            java.join("17.0.3-tem/bin").display(),
+            java.join("17.0.3-tem").display()
+        ))
+        .code(0);
+
+    Ok(())
+}
+
+#[test]
+#[serial]
+fn should_fail_for_version_not_installed() -> Result<(), Box<dyn std::error::Error>> {
+    let sdkman_dir = support::virtual_env(java_env());
+
+    env::set_var("SDKMAN_DIR", sdkman_dir.path().as_os_str());
+    Command::new(assert_cmd::cargo::cargo_bin!("use"))
+        .args(["java", "21.0.2-tem"])
+        .assert()
+        .failure()
+        .stderr(contains("java 21.0.2-tem is not installed on your system"))
+        .stdout("")
+        .code(1);
+
+    Command::new(assert_cmd::cargo::cargo_bin!("use"))
+        .args(["jvaa", "17.0.3-tem"])
+        .assert()
+        .failure()
+        .stderr(contains("jvaa is not a valid candidate."))
+        .code(1);
+
+    Ok(())
+}
+
+#[cfg(unix)]
+#[test]
+#[serial]
+fn should_print_exports_that_every_installed_shell_evaluates(
+) -> Result<(), Box<dyn std::error::Error>> {
+    let sdkman_dir = support::virtual_env(java_env());
+    let java = sdkman_dir.path().join("candidates/java");
+    let path = r#"/usr/bin:/opt/o'neil "tools" $HOME \bin"#;
+
+    env::set_var("SDKMAN_DIR", sdkman_dir.path().as_os_str());
+    for dialect in DIALECTS.iter().filter(|d| installed(d.shell)) {
+        let output = Command::new(assert_cmd::cargo::cargo_bin!("use"))
+            .args(["java", "17.0.3-tem", "--shell", dialect.shell])
+            .env("PATH", path)
+            .output()?;
+        let script = sdkman_dir.path().join(format!("use.{}", dialect.extension));
+        std::fs::write(
+            &script,
+            format!(
+                "{}\n{}\n{}\n",
+                String::from_utf8(output.stdout)?,
+                dialect.print_path,
+                dialect.print_java_home
+            ),
+        )?;
+
+        let output = std::process::Command::new(dialect.shell)
+            .args(dialect.arguments)
+            .arg(&script)
+            .output()?;
+
+        assert_eq!(
+            String::from_utf8_lossy(&output.stdout),
+            format!(
+                "{}:{}\n{}",
+                java.join("17.0.3-tem/bin").display(),
+                path,
+                java.join("17.0.3-tem").display()
+            ),
+            "{}: {}",
+            dialect.shell,
+            String::from_utf8_lossy(&output.stderr)
+        );
+    }
+
+    Ok(())
+}
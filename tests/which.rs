@@ -0,0 +1,101 @@
+#![cfg(unix)]
+
+#[cfg(test)]
+use assert_cmd::Command;
+use predicates::prelude::*;
+use predicates::str::contains;
+use serial_test::serial;
+use std::env;
+use std::fs;
+use std::os::unix::fs::PermissionsExt;
+use support::{TestCandidate, VirtualEnv};
+
+mod support;
+
+fn java_env() -> VirtualEnv {
+    VirtualEnv {
+        cli_version: "5.0.0".to_string(),
+        native_version: "0.1.0".to_string(),
+        candidates: vec![TestCandidate {
+            name: "java",
+            versions: vec!["11.0.15-tem", "17.0.3-tem", "21.0.2-tem"],
+            current_version: "11.0.15-tem",
+        }],
+    }
+}
+
+fn java_with_executables() -> tempfile::TempDir {
+    let sdkman_dir = support::virtual_env(java_env());
+    for version in ["11.0.15-tem", "17.0.3-tem", "21.0.2-tem"] {
+        let java = sdkman_dir
+            .path()
+            .join("candidates/java")
+            .join(version)
+            .join("bin/java");
+        fs::set_permissions(java, fs::Permissions::from_mode(0o755)).expect("cannot chmod");
+    }
+    sdkman_dir
+}
+
+#[test]
+#[serial]
+fn should_mark_default_and_version_on_path() -> Result<(), Box<dyn std::error::Error>> {
+    let sdkman_dir = java_with_executables();
+    let java = sdkman_dir.path().join("candidates/java");
+
+    env::set_var("SDKMAN_DIR", sdkman_dir.path().as_os_str());
+    Command::new(assert_cmd::cargo::cargo_bin!("which"))
+        .arg("java")
+        .env(
+            "PATH",
+            format!("{}:/usr/bin", java.join("17.0.3-tem/bin").display()),
+        )
+        .env("NO_COLOR", "1")
+        .assert()
+        .success()
+        .stdout(contains(format!(
+            "*   java       11.0.15-tem  {}\n",
+            java.join("11.0.15-tem/bin/java").display()
+        )))
+        .stdout(contains(format!(
+            " >  java       17.0.3-tem   {}\n",
+            java.join("17.0.3-tem/bin/java").display()
+        )))
+        .stdout(contains("21.0.2-tem").not())
+        .stdout(contains(
+            "1 more installed versions provide java, use --all to show them.",
+        ))
+        .code(0);
+
+    Command::new(assert_cmd::cargo::cargo_bin!("which"))
+        .args(["java", "--all"])
+        .env(
+            "PATH",
+            format!("{}:/usr/bin", java.join("current/bin").display()),
+        )
+        .env("NO_COLOR", "1")
+        .assert()
+        .success()
+        .stdout(contains("*>  java       11.0.15-tem"))
+        .stdout(contains("    java       21.0.2-tem"))
+        .stdout(contains("more installed versions").not())
+        .code(0);
+
+    Ok(())
+}
+
+#[test]
+#[serial]
+fn should_fail_for_executable_of_no_candidate() -> Result<(), Box<dyn std::error::Error>> {
+    let sdkman_dir = java_with_executables();
+
+    env::set_var("SDKMAN_DIR", sdkman_dir.path().as_os_str());
+    Command::new(assert_cmd::cargo::cargo_bin!("which"))
+        .arg("mvnd")
+        .assert()
+        .failure()
+        .stderr(contains("mvnd is not provided by any installed candidate."))
+        .code(1);
+
+    Ok(())
+}